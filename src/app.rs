@@ -0,0 +1,560 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+
+use crate::archive::supported_cli_versions;
+use crate::config::{
+    load_file_config, render_effective_config, resolve_sync_config, resolve_watch, FileConfig,
+};
+use crate::manifest::{
+    bump_schema_version, bump_seed_generation, load_manifest, NetworkId, SchemaVersionBump,
+    SeedGenerationBump,
+};
+use crate::shutdown::ShutdownSignal;
+use crate::sync::{run_plan_with, run_sync_with_shutdown, run_watch_with, SyncConfig, SyncRuntime};
+use crate::verify::verify_data_dir;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "rain_local_db_remote",
+    version,
+    about = "Sync rain orderbook local databases and publish their dumps."
+)]
+pub struct App {
+    /// YAML or TOML config file (falls back to SYNC_CONFIG_FILE).
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Sync every selected chain and update the manifest (default).
+    Sync(SyncArgs),
+    /// Print the sync plan per chain without running the CLI.
+    Plan(PlanArgs),
+    /// Check the local manifest and dumps for consistency.
+    Verify(DataDirArgs),
+    /// Inspect the manifest.
+    Manifest {
+        #[command(subcommand)]
+        command: ManifestCommand,
+    },
+    /// Bump the manifest schema version and the matching source constant.
+    BumpSchemaVersion {
+        #[arg(default_value = "data/manifest.yaml")]
+        manifest_path: PathBuf,
+        #[arg(default_value = "src/manifest.rs")]
+        source_path: PathBuf,
+    },
+    /// Bump the seed generation for one chain in the manifest.
+    BumpSeedGeneration {
+        chain_id: String,
+        #[arg(default_value = "data/manifest.yaml")]
+        manifest_path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ManifestCommand {
+    /// Print the manifest stored in the data directory.
+    Show {
+        #[arg(long)]
+        path: Option<PathBuf>,
+        #[command(flatten)]
+        data_dir: DataDirArgs,
+    },
+}
+
+#[derive(Debug, Default, Args)]
+pub struct SelectionArgs {
+    /// Chain id to sync; replaces the manifest, config file and SYNC_CHAIN_IDS selection.
+    #[arg(long = "chain", value_delimiter = ',')]
+    pub chains: Vec<u64>,
+    /// Orderbook name or address from the settings YAML to sync; replaces the default selection like --chain.
+    #[arg(long = "orderbook")]
+    pub orderbooks: Vec<String>,
+    /// URL of the settings YAML (falls back to SETTINGS_YAML_URL).
+    #[arg(long)]
+    pub settings_yaml_url: Option<String>,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct DataDirArgs {
+    /// Directory holding the manifest and dumps [default: data].
+    #[arg(long)]
+    pub data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct SyncArgs {
+    #[command(flatten)]
+    pub selection: SelectionArgs,
+    #[command(flatten)]
+    pub data_dir: DataDirArgs,
+    /// Directory the CLI binary is extracted into [default: bin].
+    #[arg(long)]
+    pub cli_dir: Option<PathBuf>,
+    /// URL of the rain-orderbook-cli archive (falls back to CLI_BINARY_URL).
+    #[arg(long)]
+    pub cli_binary_url: Option<String>,
+    /// Expected SHA-256 of the CLI archive (falls back to CLI_BINARY_SHA256, then a `.sha256` sidecar).
+    #[arg(long, value_name = "HEX")]
+    pub cli_binary_sha256: Option<String>,
+    /// Kill a chain's CLI run after N seconds (falls back to CLI_TIMEOUT_SECS).
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub cli_timeout: Option<u64>,
+    /// Re-run a failed CLI sync up to N times, resuming from the partial database (falls back to CLI_RETRIES).
+    #[arg(long, value_name = "COUNT")]
+    pub cli_retries: Option<u32>,
+    /// Prometheus textfile to write after each run (falls back to METRICS_TEXTFILE_PATH).
+    #[arg(long)]
+    pub metrics_path: Option<PathBuf>,
+    /// Keep running and re-sync every N seconds (falls back to SYNC_WATCH_INTERVAL_SECS).
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub watch: Option<u64>,
+    /// Download and plan every chain, then stop before running the CLI or updating the manifest.
+    #[arg(long, conflicts_with = "watch")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    #[command(flatten)]
+    pub selection: SelectionArgs,
+    #[command(flatten)]
+    pub data_dir: DataDirArgs,
+}
+
+struct CommandContext {
+    runtime: SyncRuntime,
+    file: Option<FileConfig>,
+    config: SyncConfig,
+}
+
+pub fn run<I, T>(args: I) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let app = App::try_parse_from(args).unwrap_or_else(|error| error.exit());
+    let config_path = app.config;
+    let load = || load_context(config_path.as_deref(), &ShutdownSignal::default());
+    match app.command.unwrap_or(Command::Sync(SyncArgs::default())) {
+        Command::Sync(args) => {
+            let shutdown = ShutdownSignal::install()?;
+            run_sync_command(
+                load_context(config_path.as_deref(), &shutdown)?,
+                args,
+                shutdown,
+            )
+        }
+        Command::Plan(args) => run_plan_command(load()?, args),
+        Command::Verify(args) => run_verify_command(&args.resolve(&load()?.config)),
+        Command::Manifest {
+            command: ManifestCommand::Show { path, data_dir },
+        } => {
+            let manifest_path = match path {
+                Some(path) => path,
+                None => data_dir.resolve(&load()?.config).join("manifest.yaml"),
+            };
+            run_manifest_show_command(manifest_path)
+        }
+        Command::BumpSchemaVersion {
+            manifest_path,
+            source_path,
+        } => {
+            let bump = run_bump_schema_version(manifest_path, source_path)?;
+            println!(
+                "Bumped manifest schema version from {} to {}",
+                bump.previous, bump.next
+            );
+            println!("previous={}", bump.previous);
+            println!("next={}", bump.next);
+            Ok(())
+        }
+        Command::BumpSeedGeneration {
+            chain_id,
+            manifest_path,
+        } => {
+            let bump = run_bump_seed_generation(&chain_id, manifest_path)?;
+            println!(
+                "Bumped seed generation for chain {} from {} to {}",
+                u64::from(bump.network_id),
+                bump.previous,
+                bump.next
+            );
+            println!("previous={}", bump.previous);
+            println!("next={}", bump.next);
+            Ok(())
+        }
+    }
+}
+
+fn load_context(config_path: Option<&Path>, shutdown: &ShutdownSignal) -> Result<CommandContext> {
+    let env = std::env::vars().collect();
+    let file = load_file_config(config_path, &env)?;
+    let runtime = SyncRuntime::from_env(file.as_ref(), shutdown)?;
+    let config = resolve_sync_config(file.as_ref(), &runtime.env)?;
+    Ok(CommandContext {
+        runtime,
+        file,
+        config,
+    })
+}
+
+impl DataDirArgs {
+    fn resolve(&self, config: &SyncConfig) -> PathBuf {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(|| config.db_dir.clone())
+    }
+}
+
+impl SelectionArgs {
+    fn apply_to(self, config: &mut SyncConfig) {
+        if !self.chains.is_empty() || !self.orderbooks.is_empty() {
+            config.chain_ids = self.chains;
+            config.orderbooks = self.orderbooks;
+            config.selected_chains_only = true;
+        }
+        if let Some(url) = self.settings_yaml_url {
+            config.settings_yaml_url = Some(url);
+        }
+    }
+}
+
+impl SyncArgs {
+    fn apply_to(self, config: &mut SyncConfig) {
+        if let Some(data_dir) = self.data_dir.data_dir {
+            config.db_dir = data_dir;
+        }
+        if let Some(cli_dir) = self.cli_dir {
+            config.cli_dir = cli_dir;
+        }
+        if let Some(url) = self.cli_binary_url {
+            config.cli_binary_url = Some(url);
+        }
+        if let Some(checksum) = self.cli_binary_sha256 {
+            config.cli_binary_sha256 = Some(checksum);
+        }
+        if let Some(secs) = self.cli_timeout {
+            config.cli_timeout_secs = Some(secs);
+        }
+        if let Some(retries) = self.cli_retries {
+            config.cli_retries = retries;
+        }
+        if let Some(path) = self.metrics_path {
+            config.metrics_path = Some(path);
+        }
+        config.dry_run = self.dry_run;
+        self.selection.apply_to(config);
+    }
+}
+
+fn run_sync_command(
+    context: CommandContext,
+    args: SyncArgs,
+    shutdown: ShutdownSignal,
+) -> Result<()> {
+    let CommandContext {
+        runtime,
+        file,
+        mut config,
+    } = context;
+    let watch = match args.watch {
+        None if args.dry_run => None,
+        interval => resolve_watch(
+            file.as_ref(),
+            &runtime.env,
+            interval.map(Duration::from_secs),
+        )?,
+    };
+    args.apply_to(&mut config);
+    println!(
+        "Effective configuration:\n{}",
+        render_effective_config(&config, &runtime.env)
+    );
+    match watch {
+        Some(watch) => run_watch_with(runtime, config, watch, shutdown),
+        None => run_sync_with_shutdown(runtime, config, shutdown),
+    }
+}
+
+fn run_plan_command(context: CommandContext, args: PlanArgs) -> Result<()> {
+    let CommandContext {
+        runtime,
+        mut config,
+        ..
+    } = context;
+    if let Some(data_dir) = args.data_dir.data_dir {
+        config.db_dir = data_dir;
+    }
+    args.selection.apply_to(&mut config);
+    println!(
+        "Effective configuration:\n{}",
+        render_effective_config(&config, &runtime.env)
+    );
+    let plans = run_plan_with(runtime, config)?;
+    if plans.is_empty() {
+        println!("No chains selected; nothing to plan.");
+    }
+    Ok(())
+}
+
+fn run_verify_command(data_dir: &Path) -> Result<()> {
+    let issues = verify_data_dir(data_dir)?;
+    if issues.is_empty() {
+        println!("{} verified successfully.", data_dir.display());
+        return Ok(());
+    }
+    for issue in &issues {
+        eprintln!("  {issue}");
+    }
+    anyhow::bail!(
+        "verification of {} found {} issue(s)",
+        data_dir.display(),
+        issues.len()
+    )
+}
+
+fn run_manifest_show_command(manifest_path: PathBuf) -> Result<()> {
+    if !manifest_path.exists() {
+        anyhow::bail!("no manifest found at {}", manifest_path.display());
+    }
+    let manifest = load_manifest(&manifest_path)?;
+    let serialized =
+        serde_yaml::to_string(&manifest).context("failed to serialize manifest to YAML")?;
+    print!("{}", crate::sync::normalize_yaml(&serialized));
+    Ok(())
+}
+
+fn run_bump_schema_version(
+    manifest_path: PathBuf,
+    source_path: PathBuf,
+) -> Result<SchemaVersionBump> {
+    let next = load_manifest(&manifest_path)?.schema_version + 1;
+    supported_cli_versions(next, None).with_context(|| {
+        format!(
+            "refusing to bump manifest schema version to {next}; add its SUPPORTED_CLI_VERSIONS range in src/constants.rs first"
+        )
+    })?;
+    let bump = bump_schema_version(&manifest_path, &source_path).with_context(|| {
+        format!(
+            "failed to bump schema version in {}",
+            manifest_path.display()
+        )
+    })?;
+    Ok(bump)
+}
+
+fn run_bump_seed_generation(chain_id: &str, manifest_path: PathBuf) -> Result<SeedGenerationBump> {
+    let chain_id: u64 = chain_id
+        .parse()
+        .with_context(|| format!("failed to parse chain id '{chain_id}' as u64"))?;
+
+    let bump = bump_seed_generation(&manifest_path, NetworkId::from(chain_id))
+        .with_context(|| format!("failed to bump seed generation for chain {}", chain_id))?;
+    Ok(bump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    use crate::manifest::{Manifest, ManifestEntry};
+
+    fn parse(args: &[&str]) -> App {
+        App::try_parse_from(std::iter::once("rain_local_db_remote").chain(args.iter().copied()))
+            .expect("arguments should parse")
+    }
+
+    #[test]
+    fn parse_defaults_to_no_subcommand() {
+        assert!(parse(&[]).command.is_none());
+    }
+
+    #[test]
+    fn parse_sync_arguments_into_config() {
+        let app = parse(&[
+            "sync",
+            "--chain",
+            "10,20",
+            "--chain",
+            "30",
+            "--orderbook",
+            "arbitrum",
+            "--data-dir",
+            "out",
+            "--cli-dir",
+            "tools",
+            "--cli-timeout",
+            "600",
+            "--cli-retries",
+            "3",
+            "--dry-run",
+        ]);
+        let Some(Command::Sync(args)) = app.command else {
+            panic!("expected sync command");
+        };
+        assert_eq!(args.watch, None);
+
+        let mut config = SyncConfig {
+            chain_ids: vec![5],
+            ..SyncConfig::default()
+        };
+        args.apply_to(&mut config);
+        assert_eq!(config.chain_ids, vec![10, 20, 30]);
+        assert_eq!(config.orderbooks, vec!["arbitrum".to_string()]);
+        assert!(config.selected_chains_only);
+        assert_eq!(config.db_dir, PathBuf::from("out"));
+        assert_eq!(config.cli_dir, PathBuf::from("tools"));
+        assert!(config.cli_binary_url.is_none());
+        assert_eq!(config.cli_timeout_secs, Some(600));
+        assert_eq!(config.cli_retries, 3);
+        assert!(config.dry_run);
+    }
+
+    #[test]
+    fn parse_sync_without_selection_keeps_configured_chains() {
+        let Some(Command::Sync(args)) = parse(&["sync"]).command else {
+            panic!("expected sync command");
+        };
+        let mut config = SyncConfig {
+            chain_ids: vec![5],
+            ..SyncConfig::default()
+        };
+        args.apply_to(&mut config);
+        assert_eq!(config.chain_ids, vec![5]);
+        assert!(!config.selected_chains_only);
+    }
+
+    #[test]
+    fn parse_manifest_show_with_custom_path() {
+        let app = parse(&["manifest", "show", "--path", "m.yaml"]);
+        let Some(Command::Manifest {
+            command: ManifestCommand::Show { path, .. },
+        }) = app.command
+        else {
+            panic!("expected manifest show command");
+        };
+        assert_eq!(path, Some(PathBuf::from("m.yaml")));
+    }
+
+    #[test]
+    fn parse_rejects_zero_watch_interval() {
+        let result = App::try_parse_from(["rain_local_db_remote", "sync", "--watch", "0"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_rejects_extra_bump_arguments() {
+        let result =
+            App::try_parse_from(["rain_local_db_remote", "bump-schema-version", "a", "b", "c"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn run_bump_schema_version_refuses_schemas_without_supported_cli_versions() -> Result<()> {
+        let dir = tempdir()?;
+        let manifest_path = dir.path().join("manifest.yaml");
+        let source_path = dir.path().join("manifest.rs");
+
+        let manifest = Manifest {
+            schema_version: 999,
+            ..Manifest::new()
+        };
+        let manifest_yaml = serde_yaml::to_string(&manifest)?;
+        let source = "pub const CURRENT_SCHEMA_VERSION: u32 = 999;\n";
+        fs::write(&manifest_path, &manifest_yaml)?;
+        fs::write(&source_path, source)?;
+
+        let err = run_bump_schema_version(manifest_path.clone(), source_path.clone())
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            err.contains("refusing to bump manifest schema version to 1000"),
+            "{err}"
+        );
+        assert_eq!(fs::read_to_string(&manifest_path)?, manifest_yaml);
+        assert_eq!(fs::read_to_string(&source_path)?, source);
+        Ok(())
+    }
+
+    #[test]
+    fn run_bump_seed_generation_bumps_seed_generation() -> Result<()> {
+        let dir = tempdir()?;
+        let manifest_path = dir.path().join("manifest.yaml");
+
+        let chain_id = 42u64;
+        let mut manifest = Manifest::new();
+        manifest.networks.insert(
+            NetworkId::from(chain_id),
+            ManifestEntry {
+                dump_url: "https://example.com/dump.sql.gz".to_string(),
+                dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
+                seed_generation: 7,
+                dump_sha256: None,
+                cli_version: None,
+            },
+        );
+        fs::write(&manifest_path, serde_yaml::to_string(&manifest)?)?;
+
+        let bump = run_bump_seed_generation(&chain_id.to_string(), manifest_path.clone())?;
+
+        assert_eq!(bump.network_id, NetworkId::from(chain_id));
+        assert_eq!(bump.previous, 7);
+        assert_eq!(bump.next, 8);
+
+        let stored: Manifest = serde_yaml::from_str(&fs::read_to_string(&manifest_path)?)?;
+        assert_eq!(
+            stored
+                .networks
+                .get(&NetworkId::from(chain_id))
+                .expect("entry exists")
+                .seed_generation,
+            8
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn run_bump_seed_generation_errors_on_invalid_chain_id() {
+        let err = run_bump_seed_generation("abc", PathBuf::from("unused.yaml"))
+            .expect_err("should error on invalid id");
+        assert!(err
+            .to_string()
+            .contains("failed to parse chain id 'abc' as u64"));
+    }
+
+    #[test]
+    fn run_verify_command_reports_issue_count() {
+        let dir = tempdir().unwrap();
+        let mut manifest = Manifest::new();
+        manifest.networks.insert(
+            NetworkId::from(5u64),
+            ManifestEntry {
+                dump_url: "https://example.com/5.sql.gz".to_string(),
+                dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
+                seed_generation: 1,
+                dump_sha256: None,
+                cli_version: None,
+            },
+        );
+        fs::write(
+            dir.path().join("manifest.yaml"),
+            serde_yaml::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        let err = run_verify_command(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("found 1 issue(s)"));
+    }
+}
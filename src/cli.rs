@@ -1,9 +1,21 @@
-use std::path::Path;
-use std::process::Command;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 
-use crate::constants::API_TOKEN_ENV_VARS;
+use crate::constants::{
+    API_TOKEN_ENV_VARS, CLI_LOG_TAIL_LINES, CLI_POLL_INTERVAL, CLI_TERMINATE_GRACE,
+};
+use crate::report::CliProgressEvent;
+use crate::settings::{apply_local_db_sync_tuning, parse_settings, LocalDbSyncTuning};
+use crate::shutdown::ShutdownSignal;
+use crate::sync::normalize_yaml;
 
 #[derive(Debug, Clone)]
 pub struct RunCliSyncOptions {
@@ -14,9 +26,37 @@ pub struct RunCliSyncOptions {
     pub settings_yaml: String,
     pub start_block: Option<u64>,
     pub end_block: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub tuning: LocalDbSyncTuning,
+    pub shutdown: ShutdownSignal,
 }
 
-pub fn run_cli_sync(options: &RunCliSyncOptions) -> Result<()> {
+#[derive(Debug)]
+pub struct CliTimeoutError {
+    pub chain_id: u64,
+    pub timeout: Duration,
+    pub log_path: PathBuf,
+}
+
+impl fmt::Display for CliTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CLI sync for chain {} timed out after {}s; output was logged to {}",
+            self.chain_id,
+            self.timeout.as_secs(),
+            self.log_path.display()
+        )
+    }
+}
+
+impl std::error::Error for CliTimeoutError {}
+
+pub fn is_cli_timeout(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<CliTimeoutError>())
+}
+
+pub fn run_cli_sync(options: &RunCliSyncOptions) -> Result<CliRunSummary> {
     let db_parent = Path::new(&options.db_path)
         .parent()
         .map(|p| p.to_path_buf())
@@ -36,6 +76,293 @@ pub fn run_cli_sync(options: &RunCliSyncOptions) -> Result<()> {
         )
     })?;
 
+    let settings_yaml = tuned_settings_yaml(options)?;
+    let settings_file = SecretFile::create(
+        &std::env::temp_dir(),
+        &format!("rain-local-db-{}-settings", options.chain_id),
+        &settings_yaml,
+    )?;
+    let args = build_cli_args(options, &settings_file.path.display().to_string());
+    println!("Running: {}", format_invocation(options, &args));
+
+    let log_path = cli_log_path(options);
+    let log = File::create(&log_path)
+        .with_context(|| format!("failed to create CLI log {}", log_path.display()))?;
+    let mut command = Command::new(&options.cli_binary);
+    command
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env(API_TOKEN_ENV_VARS[0], &api_token);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    let mut child = command
+        .spawn()
+        .with_context(|| "failed to spawn rain-orderbook-cli")?;
+
+    let capture = Mutex::new(CliCapture {
+        log,
+        tail: VecDeque::new(),
+        events: Vec::new(),
+    });
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let label = format!("[chain {}]", options.chain_id);
+    let waited = std::thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| capture_output(stdout, &capture, |line| println!("{label} {line}")));
+        }
+        if let Some(stderr) = stderr {
+            scope.spawn(|| capture_output(stderr, &capture, |line| eprintln!("{label} {line}")));
+        }
+        wait_with_timeout(&mut child, options.timeout, &options.shutdown)
+    });
+    let capture = capture
+        .into_inner()
+        .unwrap_or_else(|error| error.into_inner());
+    let status = match waited.with_context(|| "failed to wait for rain-orderbook-cli")? {
+        Some(status) => status,
+        None if options.shutdown.is_requested() => anyhow::bail!(
+            "CLI sync for chain {} was stopped by a shutdown request; output was logged to {}",
+            options.chain_id,
+            log_path.display()
+        ),
+        None => {
+            return Err(CliTimeoutError {
+                chain_id: options.chain_id,
+                timeout: options.timeout.unwrap_or_default(),
+                log_path,
+            }
+            .into())
+        }
+    };
+
+    if !status.success() {
+        let tail: Vec<&str> = capture.tail.iter().map(String::as_str).collect();
+        anyhow::bail!(
+            "CLI sync failed for chain {} (exit code {:?}); last {} lines of {}:\n  {}",
+            options.chain_id,
+            status.code(),
+            tail.len(),
+            log_path.display(),
+            tail.join("\n  ")
+        );
+    }
+
+    Ok(CliRunSummary {
+        log_path,
+        events: capture.events,
+    })
+}
+
+fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    shutdown: &ShutdownSignal,
+) -> std::io::Result<Option<ExitStatus>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if shutdown.is_requested() {
+            eprintln!(
+                "Shutdown requested; stopping rain-orderbook-cli (pid {})",
+                child.id()
+            );
+            terminate_process_group(child)?;
+            return Ok(None);
+        }
+        if let (Some(timeout), Some(deadline)) = (timeout, deadline) {
+            if Instant::now() >= deadline {
+                eprintln!(
+                    "rain-orderbook-cli (pid {}) exceeded its {}s timeout; sending SIGTERM",
+                    child.id(),
+                    timeout.as_secs()
+                );
+                terminate_process_group(child)?;
+                return Ok(None);
+            }
+        }
+        std::thread::sleep(CLI_POLL_INTERVAL);
+    }
+}
+
+fn wait_until(child: &mut Child, deadline: Instant) -> std::io::Result<Option<ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        std::thread::sleep((deadline - now).min(CLI_POLL_INTERVAL));
+    }
+}
+
+// The child was spawned with `process_group(0)`, so its pid is also the id of the
+// process group holding the CLI and anything it forked. That id stays reserved only
+// until the child is reaped; SIGKILL is therefore sent only while `try_wait()` still
+// reports the child as running, never after a successful wait.
+#[cfg(unix)]
+fn terminate_process_group(child: &mut Child) -> std::io::Result<()> {
+    let group = -(child.id() as libc::pid_t);
+    // SAFETY: kill(2) takes no pointers and cannot violate memory safety. The child
+    // has not been reaped yet, so `group` still names the group it leads.
+    unsafe { libc::kill(group, libc::SIGTERM) };
+    if wait_until(child, Instant::now() + CLI_TERMINATE_GRACE)?.is_some() {
+        return Ok(());
+    }
+    if child.try_wait()?.is_none() {
+        eprintln!(
+            "Warning: rain-orderbook-cli (pid {}) ignored SIGTERM; sending SIGKILL",
+            child.id()
+        );
+        // SAFETY: as above; `try_wait()` just reported the child as running, so it
+        // has not been reaped and the group id cannot have been reused.
+        unsafe { libc::kill(group, libc::SIGKILL) };
+        child.wait()?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn terminate_process_group(child: &mut Child) -> std::io::Result<()> {
+    child.kill()?;
+    child.wait().map(|_| ())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliRunSummary {
+    pub log_path: PathBuf,
+    pub events: Vec<CliProgressEvent>,
+}
+
+pub fn cli_log_path(options: &RunCliSyncOptions) -> PathBuf {
+    Path::new(&options.db_path).with_file_name(format!("{}.cli.log", options.chain_id))
+}
+
+/// Parses the CLI's `Fetched <n> batches` and `Processed blocks <from> to <to>`
+/// progress lines, optionally behind a tracing timestamp, level and target.
+pub fn parse_progress_line(line: &str) -> Option<CliProgressEvent> {
+    let message = progress_message(line)?;
+    if let Some(rest) = message.strip_prefix("Fetched ") {
+        let (count, rest) = leading_number(rest)?;
+        return is_word(rest, "batches").then_some(CliProgressEvent::BatchesFetched { count });
+    }
+    let rest = message.strip_prefix("Processed blocks ")?;
+    let (_, rest) = leading_number(rest)?;
+    let (block, rest) = leading_number(rest.strip_prefix(" to ")?)?;
+    (rest.is_empty() || rest.starts_with(' '))
+        .then_some(CliProgressEvent::BlocksProcessed { block })
+}
+
+fn progress_message(line: &str) -> Option<&str> {
+    let mut message = line.trim();
+    if let Some((timestamp, rest)) = message.split_once(' ') {
+        if chrono::DateTime::parse_from_rfc3339(timestamp).is_ok() {
+            message = rest.trim_start();
+        }
+    }
+    if let Some((level, rest)) = message.split_once(' ') {
+        match level {
+            "TRACE" | "DEBUG" | "INFO" => message = rest.trim_start(),
+            "WARN" | "ERROR" => return None,
+            _ => {}
+        }
+    }
+    if let Some((target, rest)) = message.split_once(' ') {
+        if target.ends_with(':') && target.contains("::") {
+            message = rest.trim_start();
+        }
+    }
+    Some(message)
+}
+
+fn leading_number(text: &str) -> Option<(u64, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+fn is_word(text: &str, word: &str) -> bool {
+    text.strip_prefix(' ')
+        .and_then(|rest| rest.strip_prefix(word))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+struct CliCapture {
+    log: File,
+    tail: VecDeque<String>,
+    events: Vec<CliProgressEvent>,
+}
+
+fn capture_output(reader: impl Read, capture: &Mutex<CliCapture>, echo: impl Fn(&str)) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\r', '\n']);
+        echo(line);
+
+        let mut capture = capture.lock().unwrap_or_else(|error| error.into_inner());
+        if let Err(error) = writeln!(capture.log, "{line}") {
+            eprintln!("Failed to write CLI log: {error}");
+        }
+        if capture.tail.len() == CLI_LOG_TAIL_LINES {
+            capture.tail.pop_front();
+        }
+        capture.tail.push_back(line.to_string());
+        if let Some(event) = parse_progress_line(line) {
+            capture.events.push(event);
+        }
+    }
+}
+
+fn tuned_settings_yaml(options: &RunCliSyncOptions) -> Result<String> {
+    if options.tuning.is_empty() {
+        return Ok(options.settings_yaml.clone());
+    }
+
+    let mut settings = parse_settings(&options.settings_yaml)?;
+    let network = apply_local_db_sync_tuning(&mut settings, options.chain_id, &options.tuning)
+        .with_context(|| {
+            format!(
+                "failed to apply local-db-sync overrides for chain {}",
+                options.chain_id
+            )
+        })?;
+    let applied: Vec<String> = options
+        .tuning
+        .options()
+        .iter()
+        .map(|(key, value)| format!("{key} {value}"))
+        .collect();
+    println!(
+        "Using {} for chain {} ({network})",
+        applied.join(", "),
+        options.chain_id
+    );
+    Ok(normalize_yaml(&serde_yaml::to_string(&settings).context(
+        "failed to serialize settings YAML with chain overrides",
+    )?))
+}
+
+pub fn describe_cli_invocation(options: &RunCliSyncOptions) -> String {
+    format_invocation(options, &build_cli_args(options, "<settings-file>"))
+}
+
+fn build_cli_args(options: &RunCliSyncOptions, settings_file: &str) -> Vec<String> {
     let mut args = vec![
         "local-db".to_string(),
         "sync".to_string(),
@@ -43,10 +370,8 @@ pub fn run_cli_sync(options: &RunCliSyncOptions) -> Result<()> {
         options.db_path.clone(),
         "--chain-id".to_string(),
         options.chain_id.to_string(),
-        "--api-token".to_string(),
-        api_token,
-        "--settings-yaml".to_string(),
-        options.settings_yaml.clone(),
+        "--settings-file".to_string(),
+        settings_file.to_string(),
     ];
 
     if let Some(start) = options.start_block {
@@ -59,34 +384,50 @@ pub fn run_cli_sync(options: &RunCliSyncOptions) -> Result<()> {
         args.push(end.to_string());
     }
 
-    let mut log_args = args.clone();
-    if let Some(index) = log_args.iter().position(|arg| arg == "--api-token") {
-        if let Some(value) = log_args.get_mut(index + 1) {
-            *value = "***".to_string();
-        }
-    }
-    if let Some(index) = log_args.iter().position(|arg| arg == "--settings-yaml") {
-        if let Some(value) = log_args.get_mut(index + 1) {
-            *value = "<inline-yaml>".to_string();
-        }
-    }
+    args
+}
 
-    println!("Running: {} {}", options.cli_binary, log_args.join(" "));
+fn format_invocation(options: &RunCliSyncOptions, args: &[String]) -> String {
+    format!(
+        "{}=*** {} {}",
+        API_TOKEN_ENV_VARS[0],
+        options.cli_binary,
+        args.join(" ")
+    )
+}
 
-    let status = Command::new(&options.cli_binary)
-        .args(&args)
-        .status()
-        .with_context(|| "failed to spawn rain-orderbook-cli")?;
+struct SecretFile {
+    path: PathBuf,
+}
 
-    if !status.success() {
-        anyhow::bail!(
-            "CLI sync failed for chain {} (exit code {:?})",
-            options.chain_id,
-            status.code()
-        );
+impl SecretFile {
+    fn create(dir: &Path, prefix: &str, contents: &str) -> Result<Self> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let path = dir.join(format!(
+            "{prefix}-{}-{}.yaml",
+            std::process::id(),
+            fastrand::u64(..)
+        ));
+        let secret = Self { path };
+        options
+            .open(&secret.path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .with_context(|| format!("failed to write {}", secret.path.display()))?;
+        Ok(secret)
     }
+}
 
-    Ok(())
+impl Drop for SecretFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
 }
 
 #[cfg(test)]
@@ -94,17 +435,37 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    fn options(cli_binary: &Path, db_path: &Path, chain_id: u64) -> RunCliSyncOptions {
+        RunCliSyncOptions {
+            cli_binary: cli_binary.display().to_string(),
+            db_path: db_path.display().to_string(),
+            chain_id,
+            api_token: Some("token".to_string()),
+            settings_yaml: "settings: true".to_string(),
+            start_block: None,
+            end_block: None,
+            timeout: None,
+            tuning: LocalDbSyncTuning::default(),
+            shutdown: ShutdownSignal::default(),
+        }
+    }
+
+    #[cfg(unix)]
+    fn script(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.join("rain-orderbook-cli");
+        std::fs::write(&path, format!("#!/bin/sh\n{body}")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
     #[test]
     fn run_cli_sync_requires_api_token() {
         let temp = tempdir().unwrap();
         let options = RunCliSyncOptions {
-            cli_binary: temp.path().join("cli").display().to_string(),
-            db_path: temp.path().join("db/test.db").display().to_string(),
-            chain_id: 1,
             api_token: None,
-            settings_yaml: "settings: true".to_string(),
-            start_block: None,
-            end_block: None,
+            ..options(&temp.path().join("cli"), &temp.path().join("db/test.db"), 1)
         };
 
         let err = run_cli_sync(&options).unwrap_err();
@@ -114,30 +475,32 @@ mod tests {
     #[cfg(unix)]
     #[test]
     fn run_cli_sync_invokes_cli_with_expected_arguments() {
-        use std::os::unix::fs::PermissionsExt;
-
         let temp = tempdir().unwrap();
-        let cli_path = temp.path().join("rain-orderbook-cli");
         let args_path = temp.path().join("args.txt");
+        let env_path = temp.path().join("env.txt");
+        let settings_path = temp.path().join("settings.txt");
 
-        std::fs::write(
-            &cli_path,
-            format!("#!/bin/sh\necho \"$@\" > {}\n", args_path.display()),
-        )
-        .unwrap();
-        let mut perms = std::fs::metadata(&cli_path).unwrap().permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&cli_path, perms).unwrap();
+        let cli_path = script(
+            temp.path(),
+            &format!(
+                "echo \"$@\" > {args}\necho \"$HYPERRPC_API_TOKEN\" > {env}\n\
+while [ $# -gt 0 ]; do\n\
+  if [ \"$1\" = --settings-file ]; then ls -l \"$2\" > {settings}; cat \"$2\" >> {settings}; fi\n\
+  shift\n\
+done\n",
+                args = args_path.display(),
+                env = env_path.display(),
+                settings = settings_path.display(),
+            ),
+        );
 
         let db_path = temp.path().join("nested/db.sqlite");
         let options = RunCliSyncOptions {
-            cli_binary: cli_path.display().to_string(),
-            db_path: db_path.display().to_string(),
-            chain_id: 42161,
-            api_token: Some("token".to_string()),
+            api_token: Some("secret-token".to_string()),
             settings_yaml: "test: value".to_string(),
             start_block: Some(100),
             end_block: Some(200),
+            ..options(&cli_path, &db_path, 42161)
         };
 
         run_cli_sync(&options).unwrap();
@@ -148,31 +511,41 @@ mod tests {
         assert!(captured.contains(db_path.to_str().unwrap()));
         assert!(captured.contains("--start-block 100"));
         assert!(captured.contains("--end-block 200"));
-        assert!(captured.contains("--settings-yaml"));
+        assert!(captured.contains("--settings-file"));
+        assert!(
+            !captured.contains("secret-token"),
+            "token leaked into argv: {captured}"
+        );
+        assert!(
+            !captured.contains("test: value"),
+            "settings leaked into argv: {captured}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&env_path).unwrap().trim(),
+            "secret-token"
+        );
+
+        let settings = std::fs::read_to_string(&settings_path).unwrap();
+        assert!(settings.starts_with("-rw-------"), "{settings}");
+        assert!(settings.ends_with("test: value"), "{settings}");
+        let settings_file = captured
+            .split_whitespace()
+            .skip_while(|arg| *arg != "--settings-file")
+            .nth(1)
+            .unwrap();
+        assert!(!Path::new(settings_file).exists());
     }
 
     #[cfg(unix)]
     #[test]
     fn run_cli_sync_propagates_cli_failure() {
-        use std::os::unix::fs::PermissionsExt;
-
         let temp = tempdir().unwrap();
-        let cli_path = temp.path().join("rain-orderbook-cli");
-        std::fs::write(&cli_path, "#!/bin/sh\nexit 42\n").unwrap();
-        let mut perms = std::fs::metadata(&cli_path).unwrap().permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&cli_path, perms).unwrap();
-
-        let db_path = temp.path().join("db/failure.db");
-        let options = RunCliSyncOptions {
-            cli_binary: cli_path.display().to_string(),
-            db_path: db_path.display().to_string(),
-            chain_id: 10,
-            api_token: Some("token".to_string()),
-            settings_yaml: "settings: true".to_string(),
-            start_block: None,
-            end_block: None,
-        };
+        let cli_path = script(
+            temp.path(),
+            "i=1\nwhile [ $i -le 25 ]; do echo \"line $i\"; i=$((i + 1)); done\n\
+sleep 0.2\necho 'rpc error: rate limited' >&2\nexit 42\n",
+        );
+        let options = options(&cli_path, &temp.path().join("db/failure.db"), 10);
 
         let err = run_cli_sync(&options).expect_err("cli failure should propagate");
         assert!(
@@ -183,5 +556,219 @@ mod tests {
             temp.path().join("db").exists(),
             "database directory should be created before failure"
         );
+        let message = err.to_string();
+        let log_path = temp.path().join("db/10.cli.log");
+        assert!(
+            message.contains(&format!("last 20 lines of {}", log_path.display())),
+            "{message}"
+        );
+        assert!(message.contains("rpc error: rate limited"), "{message}");
+        assert!(message.contains("line 25"), "{message}");
+        assert!(!message.contains("line 5\n"), "{message}");
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        assert_eq!(log.lines().count(), 26);
+        assert!(log.starts_with("line 1\n"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_cli_sync_writes_log_and_collects_progress_events() {
+        let temp = tempdir().unwrap();
+        let cli_path = script(
+            temp.path(),
+            "echo 'Fetched 12 batches for raindex'\n\
+echo 'Processed blocks 100 to 250' >&2\necho 'done'\n",
+        );
+
+        std::fs::create_dir_all(temp.path().join("data")).unwrap();
+        std::fs::write(temp.path().join("data/10.cli.log"), "stale run\n").unwrap();
+        let options = options(&cli_path, &temp.path().join("data/10.db"), 10);
+
+        let summary = run_cli_sync(&options).unwrap();
+
+        assert_eq!(summary.log_path, temp.path().join("data/10.cli.log"));
+        let mut events = summary.events.clone();
+        events.sort_by_key(|event| matches!(event, CliProgressEvent::BlocksProcessed { .. }));
+        assert_eq!(
+            events,
+            vec![
+                CliProgressEvent::BatchesFetched { count: 12 },
+                CliProgressEvent::BlocksProcessed { block: 250 },
+            ]
+        );
+        let log = std::fs::read_to_string(&summary.log_path).unwrap();
+        assert!(!log.contains("stale run"), "{log}");
+        assert!(log.contains("Fetched 12 batches for raindex\n"), "{log}");
+        assert!(log.contains("Processed blocks 100 to 250\n"), "{log}");
+        assert!(log.contains("done\n"), "{log}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_cli_sync_kills_process_group_on_timeout() {
+        let temp = tempdir().unwrap();
+        let pid_path = temp.path().join("child.pid");
+        let cli_path = script(
+            temp.path(),
+            &format!(
+                "sleep 30 &\necho $! > {pid}\necho 'waiting for rpc'\nwait\n",
+                pid = pid_path.display()
+            ),
+        );
+        let options = RunCliSyncOptions {
+            timeout: Some(Duration::from_millis(500)),
+            ..options(&cli_path, &temp.path().join("data/10.db"), 10)
+        };
+
+        let started = Instant::now();
+        let err = run_cli_sync(&options).expect_err("hung cli should time out");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(is_cli_timeout(&err), "{err:#}");
+        let timeout = err.downcast_ref::<CliTimeoutError>().unwrap();
+        assert_eq!(timeout.chain_id, 10);
+        assert_eq!(timeout.log_path, temp.path().join("data/10.cli.log"));
+        assert!(std::fs::read_to_string(&timeout.log_path)
+            .unwrap()
+            .contains("waiting for rpc"));
+
+        let pid = std::fs::read_to_string(&pid_path).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+            .is_ok_and(|stat| !stat.contains(") Z "));
+        assert!(!alive, "grandchild survived");
+        assert!(!is_cli_timeout(&anyhow::anyhow!("CLI sync failed")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_cli_sync_stops_process_group_on_shutdown() {
+        let temp = tempdir().unwrap();
+        let pid_path = temp.path().join("child.pid");
+        let cli_path = script(
+            temp.path(),
+            &format!(
+                "sleep 30 &\necho $! > {pid}\nwait\n",
+                pid = pid_path.display()
+            ),
+        );
+
+        let shutdown = ShutdownSignal::default();
+        let options = RunCliSyncOptions {
+            shutdown: shutdown.clone(),
+            ..options(&cli_path, &temp.path().join("data/10.db"), 10)
+        };
+
+        let started = Instant::now();
+        let err = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(300));
+                shutdown.request();
+            });
+            run_cli_sync(&options).expect_err("shutdown should stop the cli")
+        });
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!is_cli_timeout(&err), "{err:#}");
+        assert!(
+            err.to_string().contains("stopped by a shutdown request"),
+            "{err}"
+        );
+
+        let pid = std::fs::read_to_string(&pid_path).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+            .is_ok_and(|stat| !stat.contains(") Z "));
+        assert!(!alive, "grandchild survived");
+    }
+
+    #[test]
+    fn tuned_settings_yaml_merges_overrides_for_the_chain_network() {
+        let mut options = RunCliSyncOptions {
+            settings_yaml: "networks:\n  base:\n    chain-id: 8453\nlocal-db-sync:\n  base:\n    batch-size: 10\n".to_string(),
+            ..options(
+                Path::new("bin/rain-orderbook-cli"),
+                Path::new("data/8453.db"),
+                8453,
+            )
+        };
+        assert_eq!(
+            tuned_settings_yaml(&options).unwrap(),
+            options.settings_yaml
+        );
+
+        options.tuning = LocalDbSyncTuning {
+            batch_size: Some(500),
+            bootstrap_block_threshold: Some(10_000),
+            ..LocalDbSyncTuning::default()
+        };
+        let tuned = parse_settings(&tuned_settings_yaml(&options).unwrap()).unwrap();
+        assert_eq!(tuned["local-db-sync"]["base"]["batch-size"], 500);
+        assert_eq!(
+            tuned["local-db-sync"]["base"]["bootstrap-block-threshold"],
+            10_000
+        );
+
+        options.chain_id = 1;
+        let err = tuned_settings_yaml(&options).unwrap_err();
+        assert!(format!("{err:#}").contains("failed to apply local-db-sync overrides for chain 1"));
+    }
+
+    #[test]
+    fn parse_progress_line_recognises_batches_and_blocks() {
+        assert_eq!(
+            parse_progress_line("Fetched 7 batches (concurrency 4)"),
+            Some(CliProgressEvent::BatchesFetched { count: 7 })
+        );
+        assert_eq!(
+            parse_progress_line(
+                "2025-11-06T10:01:21.113Z  INFO rain_orderbook_cli::local_db: Fetched 3 batches"
+            ),
+            Some(CliProgressEvent::BatchesFetched { count: 3 })
+        );
+        assert_eq!(
+            parse_progress_line("INFO Processed blocks 1000 to 1500"),
+            Some(CliProgressEvent::BlocksProcessed { block: 1500 })
+        );
+        assert_eq!(parse_progress_line("processing blocks"), None);
+        assert_eq!(parse_progress_line("Applying 3 migrations"), None);
+    }
+
+    #[test]
+    fn parse_progress_line_ignores_error_lines() {
+        assert_eq!(parse_progress_line("failed to fetch batch: HTTP 429"), None);
+        assert_eq!(
+            parse_progress_line("ERROR rain_orderbook_cli::local_db: Fetched 0 batches"),
+            None
+        );
+        assert_eq!(
+            parse_progress_line("WARN could not process blocks 100 to 200"),
+            None
+        );
+        assert_eq!(
+            parse_progress_line("Fetched batches: 502 Bad Gateway"),
+            None
+        );
+        assert_eq!(parse_progress_line("Processed blocks 100 to 200abc"), None);
+    }
+
+    #[test]
+    fn describe_cli_invocation_redacts_token_and_settings() {
+        let options = RunCliSyncOptions {
+            api_token: Some("secret-token".to_string()),
+            settings_yaml: "networks: {}".to_string(),
+            start_block: Some(5),
+            ..options(
+                Path::new("bin/rain-orderbook-cli"),
+                Path::new("data/10.db"),
+                10,
+            )
+        };
+
+        let invocation = describe_cli_invocation(&options);
+        assert_eq!(
+            invocation,
+            "HYPERRPC_API_TOKEN=*** bin/rain-orderbook-cli local-db sync --db-path data/10.db \
+--chain-id 10 --settings-file <settings-file> --start-block 5"
+        );
+        assert!(!invocation.contains("secret-token"));
     }
 }
@@ -1,12 +1,49 @@
+use std::time::Duration;
+
 use num_format::{Locale, ToFormattedString};
 
 pub const CLI_ARCHIVE_NAME: &str = "rain-orderbook-cli.tar.gz";
+pub const CLI_BINARY_NAME: &str = "rain-orderbook-cli";
+pub const CLI_CACHE_MAX_ENTRIES: usize = 3;
+pub const CLI_VERSION_REQUIREMENT_ENV_VAR: &str = "CLI_VERSION_REQUIREMENT";
+pub const SUPPORTED_CLI_VERSIONS: &[(u32, &str)] = &[(1, ">=0.0.1, <0.1.0")];
 pub const CLI_BINARY_URL_ENV_VAR: &str = "CLI_BINARY_URL";
+pub const CLI_BINARY_MIRRORS_ENV_VAR: &str = "CLI_BINARY_MIRRORS";
+pub const CLI_BINARY_SHA256_ENV_VAR: &str = "CLI_BINARY_SHA256";
+pub const CLI_BINARY_SHA256_REQUIRED_ENV_VAR: &str = "CLI_BINARY_SHA256_REQUIRED";
+pub const RELEASE_MIRRORS_ENV_VAR: &str = "RELEASE_MIRRORS";
 pub const RELEASE_DOWNLOAD_URL_TEMPLATE: &str =
     "https://github.com/findolor/local_db_remote/releases/latest/download/{file}";
+pub const DEFAULT_RELEASE_TAG: &str = "latest";
+pub const RELEASE_TAG_ENV_VAR: &str = "RELEASE_TAG";
 pub const API_TOKEN_ENV_VARS: &[&str] = &["HYPERRPC_API_TOKEN"];
+pub const CLI_LOG_TAIL_LINES: usize = 20;
+pub const CLI_TIMEOUT_ENV_VAR: &str = "CLI_TIMEOUT_SECS";
+pub const CLI_RETRIES_ENV_VAR: &str = "CLI_RETRIES";
+pub const CLI_RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+pub const CLI_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(120);
+pub const CLI_TERMINATE_GRACE: Duration = Duration::from_secs(10);
+pub const CLI_POLL_INTERVAL: Duration = Duration::from_millis(100);
 pub const SETTINGS_YAML_ENV_VAR: &str = "SETTINGS_YAML_URL";
 pub const SYNC_CHAIN_IDS_ENV_VAR: &str = "SYNC_CHAIN_IDS";
+pub const DUMP_DOWNLOAD_CONCURRENCY_ENV_VAR: &str = "DUMP_DOWNLOAD_CONCURRENCY";
+pub const METRICS_TEXTFILE_ENV_VAR: &str = "METRICS_TEXTFILE_PATH";
+pub const SYNC_WATCH_INTERVAL_ENV_VAR: &str = "SYNC_WATCH_INTERVAL_SECS";
+pub const SYNC_WATCH_MAX_BACKOFF_ENV_VAR: &str = "SYNC_WATCH_MAX_BACKOFF_SECS";
+pub const PUBLISH_DIR_ENV_VAR: &str = "PUBLISH_DIR";
+pub const PUBLISH_S3_ENDPOINT_ENV_VAR: &str = "PUBLISH_S3_ENDPOINT";
+pub const PUBLISH_S3_BUCKET_ENV_VAR: &str = "PUBLISH_S3_BUCKET";
+pub const PUBLISH_S3_REGION_ENV_VAR: &str = "PUBLISH_S3_REGION";
+pub const PUBLISH_S3_PREFIX_ENV_VAR: &str = "PUBLISH_S3_PREFIX";
+pub const PUBLISH_S3_ACCESS_KEY_ENV_VAR: &str = "AWS_ACCESS_KEY_ID";
+pub const PUBLISH_S3_SECRET_KEY_ENV_VAR: &str = "AWS_SECRET_ACCESS_KEY";
+pub const HTTP_CONNECT_TIMEOUT_ENV_VAR: &str = "HTTP_CONNECT_TIMEOUT_SECS";
+pub const HTTP_READ_TIMEOUT_ENV_VAR: &str = "HTTP_READ_TIMEOUT_SECS";
+pub const HTTP_MAX_ATTEMPTS_ENV_VAR: &str = "HTTP_MAX_ATTEMPTS";
+pub const HTTP_AUTH_ENV_VAR: &str = "HTTP_AUTH";
+pub const HTTP_CACHE_DIR_ENV_VAR: &str = "HTTP_CACHE_DIR";
+pub const SYNC_CONFIG_FILE_ENV_VAR: &str = "SYNC_CONFIG_FILE";
+pub const RELEASE_DOWNLOAD_URL_TEMPLATE_ENV_VAR: &str = "RELEASE_DOWNLOAD_URL_TEMPLATE";
 
 pub fn format_number(value: u64) -> String {
     value.to_formatted_string(&Locale::en)
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -14,6 +15,21 @@ pub struct SyncPlan {
     pub next_start_block: Option<u64>,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseStats {
+    pub last_synced_block: Option<u64>,
+    pub table_rows: BTreeMap<String, u64>,
+    pub orderbooks: Vec<OrderbookProgress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderbookProgress {
+    pub orderbook: String,
+    pub last_block: u64,
+    /// Block time of the newest event indexed at or below `last_block`.
+    pub end_block_time_ms: Option<i64>,
+}
+
 pub fn prepare_database(db_stem: &str, db_dir: &Path) -> Result<(PathBuf, PathBuf)> {
     let db_path = db_dir.join(format!("{db_stem}.db"));
     let dump_path = db_dir.join(format!("{db_stem}.sql.gz"));
@@ -157,6 +173,17 @@ pub fn plan_sync(db_path: &Path, dump_path: &Path) -> Result<SyncPlan> {
     })
 }
 
+pub fn inspect_database(db_path: &Path) -> Result<DatabaseStats> {
+    let last_synced_block = get_last_synced_block(db_path)?;
+    let table_rows = count_table_rows(db_path)?;
+    let orderbooks = read_orderbook_progress(db_path)?;
+    Ok(DatabaseStats {
+        last_synced_block,
+        table_rows,
+        orderbooks,
+    })
+}
+
 fn load_sql_dump(sql_path: &Path, db_path: &Path, db_stem: &str) -> Result<()> {
     let sql_file = fs::File::open(sql_path).with_context(|| {
         format!(
@@ -312,6 +339,118 @@ fn get_last_synced_block(db_path: &Path) -> Result<Option<u64>> {
     Ok(value)
 }
 
+fn count_table_rows(db_path: &Path) -> Result<BTreeMap<String, u64>> {
+    if !path_exists(db_path)? {
+        return Ok(BTreeMap::new());
+    }
+
+    let tables_output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg(db_path)
+        .arg("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name;")
+        .output();
+
+    warn_if_sqlite_missing(&tables_output);
+    let tables_output = match tables_output {
+        Ok(output) if output.status.success() => output,
+        _ => return Ok(BTreeMap::new()),
+    };
+
+    let tables: Vec<String> = String::from_utf8_lossy(&tables_output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    if tables.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let query = tables
+        .iter()
+        .map(|table| {
+            format!(
+                "SELECT {}, COUNT(*) FROM {}",
+                quote_literal(table),
+                quote_identifier(table)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ")
+        + ";";
+
+    let counts_output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-separator")
+        .arg("|")
+        .arg(db_path)
+        .arg(query)
+        .output();
+
+    warn_if_sqlite_missing(&counts_output);
+    let counts_output = match counts_output {
+        Ok(output) if output.status.success() => output,
+        _ => return Ok(BTreeMap::new()),
+    };
+
+    let stdout = String::from_utf8_lossy(&counts_output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let (table, count) = line.rsplit_once('|')?;
+            Some((table.to_string(), count.trim().parse::<u64>().ok()?))
+        })
+        .collect())
+}
+
+fn read_orderbook_progress(db_path: &Path) -> Result<Vec<OrderbookProgress>> {
+    if !path_exists(db_path)? {
+        return Ok(Vec::new());
+    }
+
+    let progress_output = Command::new("sqlite3")
+        .arg("-readonly")
+        .arg("-separator")
+        .arg("|")
+        .arg(db_path)
+        .arg(ORDERBOOK_PROGRESS_QUERY)
+        .output();
+
+    warn_if_sqlite_missing(&progress_output);
+    let progress_output = match progress_output {
+        Ok(output) if output.status.success() => output,
+        _ => return Ok(Vec::new()),
+    };
+
+    let stdout = String::from_utf8_lossy(&progress_output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('|');
+            let orderbook = parts.next()?.trim().to_string();
+            let last_block = parts.next()?.trim().parse::<u64>().ok()?;
+            let end_block_time_ms = parts
+                .next()
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .map(|seconds| seconds.saturating_mul(1000));
+            Some(OrderbookProgress {
+                orderbook,
+                last_block,
+                end_block_time_ms,
+            })
+        })
+        .collect())
+}
+
+const ORDERBOOK_PROGRESS_QUERY: &str = "SELECT w.orderbook_address, w.last_block, \
+(SELECT MAX(e.block_timestamp) FROM raw_events e \
+WHERE e.chain_id = w.chain_id AND e.orderbook_address = w.orderbook_address \
+AND e.block_number <= w.last_block) \
+FROM target_watermarks w ORDER BY w.orderbook_address;";
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
 fn quote_identifier(identifier: &str) -> String {
     format!("\"{}\"", identifier.replace('"', "\"\""))
 }
@@ -582,6 +721,88 @@ exit 1
         assert!(logged.contains("last_block"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn inspect_database_counts_rows_per_table() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _guard = path_mutex().lock().unwrap();
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("orderbook.db");
+        std::fs::write(&db_path, b"db").unwrap();
+
+        let bin_dir = tempdir().unwrap();
+        let sqlite_bin = bin_dir.path().join("sqlite3");
+        std::fs::write(
+            &sqlite_bin,
+            r#"#!/bin/sh
+case "$3" in
+  *sync_status*) exit 0 ;;
+  *"sqlite_master"*)
+    echo orders
+    echo vaults
+    exit 0
+    ;;
+esac
+case "$5" in
+  *"UNION ALL"*)
+    echo 'orders|12'
+    echo 'vaults|3'
+    exit 0
+    ;;
+  *target_watermarks*)
+    echo '0xabc|37818946|1762427339'
+    echo '0xdef|500|'
+    exit 0
+    ;;
+esac
+exit 1
+"#,
+        )
+        .unwrap();
+        let mut perms = std::fs::metadata(&sqlite_bin).unwrap().permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(&sqlite_bin, perms).unwrap();
+
+        let original_path = std::env::var_os("PATH");
+        let new_path = match original_path.as_ref() {
+            Some(value) => {
+                let mut combined = bin_dir.path().as_os_str().to_os_string();
+                combined.push(":");
+                combined.push(value);
+                combined
+            }
+            None => bin_dir.path().as_os_str().to_os_string(),
+        };
+        std::env::set_var("PATH", &new_path);
+
+        let stats = inspect_database(&db_path).unwrap();
+
+        match original_path {
+            Some(value) => std::env::set_var("PATH", value),
+            None => std::env::remove_var("PATH"),
+        }
+
+        assert_eq!(stats.last_synced_block, None);
+        assert_eq!(stats.table_rows.get("orders"), Some(&12));
+        assert_eq!(stats.table_rows.get("vaults"), Some(&3));
+        assert_eq!(
+            stats.orderbooks,
+            vec![
+                OrderbookProgress {
+                    orderbook: "0xabc".to_string(),
+                    last_block: 37_818_946,
+                    end_block_time_ms: Some(1_762_427_339_000),
+                },
+                OrderbookProgress {
+                    orderbook: "0xdef".to_string(),
+                    last_block: 500,
+                    end_block_time_ms: None,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn quote_identifier_escapes_quotes() {
         let quoted = quote_identifier("col\"name");
@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use anyhow::{Context, Result};
+use reqwest::blocking::RequestBuilder;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::redirect::{Attempt, Policy};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::HTTP_AUTH_ENV_VAR;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HostAuthConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bearer_token_env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub header_envs: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+struct HostCredentials {
+    bearer_token: Option<String>,
+    basic: Option<(String, Option<String>)>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+#[derive(Clone, Default)]
+pub struct HttpAuth {
+    hosts: BTreeMap<String, HostCredentials>,
+}
+
+impl fmt::Debug for HttpAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.hosts
+                    .iter()
+                    .map(|(host, credentials)| (host, credentials.kinds().join("+"))),
+            )
+            .finish()
+    }
+}
+
+impl HostCredentials {
+    fn kinds(&self) -> Vec<&'static str> {
+        let mut kinds = Vec::new();
+        if self.bearer_token.is_some() {
+            kinds.push("bearer");
+        }
+        if self.basic.is_some() {
+            kinds.push("basic");
+        }
+        if !self.headers.is_empty() {
+            kinds.push("headers");
+        }
+        kinds
+    }
+
+    fn add_header(&mut self, host: &str, name: &str, value: &str) -> Result<()> {
+        let name = HeaderName::from_bytes(name.trim().as_bytes())
+            .with_context(|| format!("invalid header name {name:?} for host {host}"))?;
+        let mut value = HeaderValue::from_str(value.trim())
+            .map_err(|_| anyhow::anyhow!("invalid value for header {name} on host {host}"))?;
+        value.set_sensitive(true);
+        self.headers.push((name, value));
+        Ok(())
+    }
+}
+
+impl HttpAuth {
+    pub fn resolve(
+        file: &BTreeMap<String, HostAuthConfig>,
+        env: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let mut auth = Self::default();
+        for (host, config) in file {
+            let credentials = auth.hosts.entry(host.to_ascii_lowercase()).or_default();
+            if let Some(key) = &config.bearer_token_env {
+                credentials.bearer_token = Some(required_env(env, key, host)?);
+            }
+            if let Some(username) = &config.username {
+                let password = config
+                    .password_env
+                    .as_deref()
+                    .map(|key| required_env(env, key, host))
+                    .transpose()?;
+                credentials.basic = Some((username.clone(), password));
+            }
+            for (name, key) in &config.header_envs {
+                credentials.add_header(host, name, &required_env(env, key, host)?)?;
+            }
+        }
+        if let Some(value) = env
+            .get(HTTP_AUTH_ENV_VAR)
+            .filter(|value| !value.trim().is_empty())
+        {
+            auth.merge_env_entries(value)?;
+        }
+        Ok(auth)
+    }
+
+    fn merge_env_entries(&mut self, value: &str) -> Result<()> {
+        let entries = value
+            .split([';', '\n'])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+        for (index, entry) in entries.enumerate() {
+            let position = index + 1;
+            let (host, spec) = entry.split_once('=').with_context(|| {
+                format!("{HTTP_AUTH_ENV_VAR} entry {position} must look like host=kind:value")
+            })?;
+            let host = host.trim().to_ascii_lowercase();
+            let (kind, secret) = spec.split_once(':').with_context(|| {
+                format!("{HTTP_AUTH_ENV_VAR} entry for host {host} must look like host=kind:value")
+            })?;
+            let credentials = self.hosts.entry(host.clone()).or_default();
+            match kind.trim() {
+                "bearer" => credentials.bearer_token = Some(secret.trim().to_string()),
+                "basic" => {
+                    let (username, password) = match secret.split_once(':') {
+                        Some((username, password)) => (username, Some(password.to_string())),
+                        None => (secret, None),
+                    };
+                    credentials.basic = Some((username.trim().to_string(), password));
+                }
+                "header" => {
+                    let (name, value) = secret.split_once(':').with_context(|| {
+                        format!(
+                            "{HTTP_AUTH_ENV_VAR} header entry for host {host} must look like host=header:Name:value"
+                        )
+                    })?;
+                    credentials.add_header(&host, name, value)?;
+                }
+                other => anyhow::bail!(
+                    "{HTTP_AUTH_ENV_VAR} entry for host {host} has unknown kind {other:?} (expected bearer, basic or header)"
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    pub fn apply(&self, url: &str, mut request: RequestBuilder) -> RequestBuilder {
+        let Some(credentials) = self.credentials_for(url) else {
+            return request;
+        };
+        if let Some(token) = &credentials.bearer_token {
+            request = request.bearer_auth(token);
+        }
+        if let Some((username, password)) = &credentials.basic {
+            request = request.basic_auth(username, password.as_ref());
+        }
+        for (name, value) in &credentials.headers {
+            request = request.header(name.clone(), value.clone());
+        }
+        request
+    }
+
+    // reqwest only strips Authorization and Cookie when a redirect changes host, so
+    // configured custom headers would follow a private asset to its CDN. Same-host
+    // redirects are followed as usual; a cross-host one is handed back to the caller,
+    // which re-applies credentials for the new host only.
+    pub fn redirect_policy() -> Policy {
+        Policy::custom(|attempt: Attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if attempt
+                .previous()
+                .last()
+                .is_some_and(|previous| same_host(previous, attempt.url()))
+            {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        })
+    }
+
+    fn credentials_for(&self, url: &str) -> Option<&HostCredentials> {
+        let parsed = reqwest::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_ascii_lowercase();
+        parsed
+            .port()
+            .and_then(|port| self.hosts.get(&format!("{host}:{port}")))
+            .or_else(|| self.hosts.get(&host))
+    }
+}
+
+pub const MAX_REDIRECTS: usize = 10;
+
+pub fn same_host(a: &Url, b: &Url) -> bool {
+    a.host_str() == b.host_str() && a.port_or_known_default() == b.port_or_known_default()
+}
+
+fn required_env(env: &HashMap<String, String>, key: &str, host: &str) -> Result<String> {
+    env.get(key)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .with_context(|| format!("credentials for host {host} require env var {key} to be set"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_reads_file_credentials_from_env() {
+        let file = BTreeMap::from([(
+            "Bucket.Example".to_string(),
+            HostAuthConfig {
+                username: Some("ci".to_string()),
+                password_env: Some("BUCKET_PASSWORD".to_string()),
+                header_envs: BTreeMap::from([("X-Api-Key".to_string(), "BUCKET_KEY".to_string())]),
+                ..HostAuthConfig::default()
+            },
+        )]);
+        let env = HashMap::from([
+            ("BUCKET_PASSWORD".to_string(), "hunter2".to_string()),
+            ("BUCKET_KEY".to_string(), "key-123".to_string()),
+        ]);
+
+        let auth = HttpAuth::resolve(&file, &env).unwrap();
+        let credentials = auth
+            .credentials_for("https://bucket.example/dumps/1.sql.gz")
+            .unwrap();
+        assert_eq!(credentials.kinds(), vec!["basic", "headers"]);
+        assert!(auth.credentials_for("https://other.example/x").is_none());
+        assert_eq!(
+            format!("{auth:?}"),
+            r#"{"bucket.example": "basic+headers"}"#
+        );
+
+        let err = HttpAuth::resolve(&file, &HashMap::new()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("require env var BUCKET_PASSWORD to be set"));
+    }
+
+    #[test]
+    fn env_entries_support_all_kinds_and_ports() {
+        let env = HashMap::from([(
+            HTTP_AUTH_ENV_VAR.to_string(),
+            "raw.example=bearer:ghp_secret; localhost:8080=basic:user:pass;localhost:8080=header:X-Trace:abc"
+                .to_string(),
+        )]);
+        let auth = HttpAuth::resolve(&BTreeMap::new(), &env).unwrap();
+
+        assert_eq!(
+            auth.credentials_for("https://raw.example/settings.yaml")
+                .unwrap()
+                .kinds(),
+            vec!["bearer"]
+        );
+        assert_eq!(
+            auth.credentials_for("http://localhost:8080/manifest.yaml")
+                .unwrap()
+                .kinds(),
+            vec!["basic", "headers"]
+        );
+        assert!(auth.credentials_for("http://localhost:9090/x").is_none());
+    }
+
+    #[test]
+    fn env_parse_errors_never_echo_secrets() {
+        for value in [
+            "raw.example=token:ghp_secret",
+            "raw.example=header:Bad Name:ghp_secret",
+            "raw.example=header:X-Key:ghp_secret\u{7f}",
+            "ghp_secret",
+        ] {
+            let env = HashMap::from([(HTTP_AUTH_ENV_VAR.to_string(), value.to_string())]);
+            let err = HttpAuth::resolve(&BTreeMap::new(), &env).unwrap_err();
+            assert!(!format!("{err:#}").contains("ghp_secret"), "{err:#}");
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::http::{redact_url, write_atomically};
+
+#[derive(Clone, Debug)]
+pub struct HttpCache {
+    dir: PathBuf,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CacheValidators {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    /// Redacted, since request URLs may carry credentials in their query string.
+    url: String,
+    #[serde(flatten)]
+    validators: CacheValidators,
+}
+
+impl CacheValidators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn conditional_headers(&self, url: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let Some(entry) = self.entry(url) else {
+            return headers;
+        };
+        let value = |value: &Option<String>| {
+            value
+                .as_deref()
+                .and_then(|value| HeaderValue::from_str(value).ok())
+        };
+        if let Some(etag) = value(&entry.validators.etag) {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = value(&entry.validators.last_modified) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified);
+        }
+        headers
+    }
+
+    pub fn read(&self, url: &str) -> Result<Vec<u8>> {
+        let body = self.body_path(url);
+        std::fs::read(&body).with_context(|| {
+            format!(
+                "server reported {} unchanged but cached copy {} is unreadable",
+                redact_url(url),
+                body.display()
+            )
+        })
+    }
+
+    pub fn copy_to(&self, url: &str, destination: &Path) -> Result<u64> {
+        let body = self.body_path(url);
+        let mut source = File::open(&body).with_context(|| {
+            format!(
+                "server reported {} unchanged but cached copy {} is unreadable",
+                redact_url(url),
+                body.display()
+            )
+        })?;
+        write_atomically(destination, |file| std::io::copy(&mut source, file))
+    }
+
+    pub fn store_bytes(&self, url: &str, validators: &CacheValidators, bytes: &[u8]) {
+        self.store(url, validators, |body| {
+            write_atomically(body, |file| {
+                std::io::Write::write_all(file, bytes).map(|_| bytes.len() as u64)
+            })
+        });
+    }
+
+    pub fn store_file(&self, url: &str, validators: &CacheValidators, source: &Path) {
+        self.store(url, validators, |body| {
+            let mut source = File::open(source)
+                .with_context(|| format!("failed to open {}", source.display()))?;
+            write_atomically(body, |file| std::io::copy(&mut source, file))
+        });
+    }
+
+    fn store(
+        &self,
+        url: &str,
+        validators: &CacheValidators,
+        write_body: impl FnOnce(&Path) -> Result<u64>,
+    ) {
+        if validators.is_empty() {
+            return;
+        }
+        let result = std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))
+            .and_then(|_| {
+                let _ = std::fs::remove_file(self.meta_path(url));
+                write_body(&self.body_path(url))
+            })
+            .and_then(|_| {
+                let entry = CacheEntry {
+                    url: redact_url(url),
+                    validators: validators.clone(),
+                };
+                let serialized = serde_yaml::to_string(&entry)?;
+                write_atomically(&self.meta_path(url), |file| {
+                    std::io::Write::write_all(file, serialized.as_bytes())
+                        .map(|_| serialized.len() as u64)
+                })
+            });
+        if let Err(error) = result {
+            eprintln!(
+                "Warning: failed to cache response for {}: {error:#}",
+                redact_url(url)
+            );
+        }
+    }
+
+    fn entry(&self, url: &str) -> Option<CacheEntry> {
+        if !self.body_path(url).exists() {
+            return None;
+        }
+        let contents = std::fs::read_to_string(self.meta_path(url)).ok()?;
+        let entry: CacheEntry = serde_yaml::from_str(&contents).ok()?;
+        (entry.url == redact_url(url) && !entry.validators.is_empty()).then_some(entry)
+    }
+
+    fn body_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.body", cache_key(url)))
+    }
+
+    fn meta_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{}.yaml", cache_key(url)))
+    }
+}
+
+fn cache_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn stored_entries_produce_conditional_headers() {
+        let temp = tempdir().unwrap();
+        let cache = HttpCache::new(temp.path());
+        let url = "https://example.com/manifest.yaml";
+        assert!(cache.conditional_headers(url).is_empty());
+
+        let validators = CacheValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+        };
+        cache.store_bytes(url, &validators, b"cached");
+
+        let headers = cache.conditional_headers(url);
+        assert_eq!(headers.get(IF_NONE_MATCH).unwrap(), "\"abc\"");
+        assert_eq!(
+            headers.get(IF_MODIFIED_SINCE).unwrap(),
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+        assert_eq!(cache.read(url).unwrap(), b"cached");
+        assert!(cache
+            .conditional_headers("https://example.com/other")
+            .is_empty());
+    }
+
+    #[test]
+    fn stored_entries_never_persist_url_credentials() {
+        let temp = tempdir().unwrap();
+        let cache = HttpCache::new(temp.path());
+        let url = "https://example.com/manifest.yaml?X-Amz-Signature=secret";
+        let validators = CacheValidators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        cache.store_bytes(url, &validators, b"cached");
+
+        let meta = std::fs::read_to_string(cache.meta_path(url)).unwrap();
+        assert!(!meta.contains("secret"), "{meta}");
+        assert_eq!(
+            cache.conditional_headers(url).get(IF_NONE_MATCH).unwrap(),
+            "\"abc\""
+        );
+    }
+
+    #[test]
+    fn responses_without_validators_are_not_cached() {
+        let temp = tempdir().unwrap();
+        let cache = HttpCache::new(temp.path());
+        cache.store_bytes("https://example.com/a", &CacheValidators::default(), b"x");
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+    }
+}
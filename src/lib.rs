@@ -1,10 +1,24 @@
+pub mod app;
 pub mod archive;
 pub mod cli;
+pub mod cli_cache;
+pub mod config;
 pub mod constants;
 pub mod database;
 pub mod http;
+pub mod http_auth;
+pub mod http_cache;
 pub mod logging;
 pub mod manifest;
+pub mod metrics;
+pub mod publish;
+pub mod release;
+pub mod report;
+pub mod settings;
+pub mod shutdown;
 pub mod sync;
+pub mod verify;
 
-pub use sync::{run_sync, run_sync_with, SyncConfig, SyncRuntime};
+pub use sync::{
+    run_sync_with, run_sync_with_shutdown, run_watch_with, SyncConfig, SyncRuntime, WatchConfig,
+};
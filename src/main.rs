@@ -1,8 +1,12 @@
-use rain_local_db_remote::run_sync;
+use rain_local_db_remote::shutdown::{is_shutdown, SHUTDOWN_EXIT_CODE};
 
 fn main() {
-    if let Err(error) = run_sync() {
+    if let Err(error) = rain_local_db_remote::app::run(std::env::args_os()) {
         eprintln!("error: {error:?}");
-        std::process::exit(1);
+        std::process::exit(if is_shutdown(&error) {
+            SHUTDOWN_EXIT_CODE
+        } else {
+            1
+        });
     }
 }
@@ -37,6 +37,19 @@ pub struct ManifestEntry {
     pub dump_timestamp: String,
     #[serde(default = "ManifestEntry::default_seed_generation")]
     pub seed_generation: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dump_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cli_version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManifestEntryUpdate {
+    pub chain_id: u64,
+    pub dump_url: String,
+    pub dump_timestamp: DateTime<Utc>,
+    pub dump_sha256: Option<String>,
+    pub cli_version: Option<String>,
 }
 
 impl ManifestEntry {
@@ -130,12 +143,7 @@ impl<'de> Deserialize<'de> for NetworkId {
     }
 }
 
-pub fn update_manifest(
-    manifest_path: &Path,
-    network_id: u64,
-    dump_url: &str,
-    timestamp: DateTime<Utc>,
-) -> Result<()> {
+pub fn update_manifest(manifest_path: &Path, update: &ManifestEntryUpdate) -> Result<()> {
     let mut manifest = load_manifest(manifest_path)?;
     if manifest.schema_version != Manifest::CURRENT_SCHEMA_VERSION {
         anyhow::bail!(
@@ -145,7 +153,7 @@ pub fn update_manifest(
         );
     }
 
-    let network_id = NetworkId::from(network_id);
+    let network_id = NetworkId::from(update.chain_id);
     let seed_generation = manifest
         .networks
         .get(&network_id)
@@ -153,16 +161,18 @@ pub fn update_manifest(
         .unwrap_or(ManifestEntry::DEFAULT_SEED_GENERATION);
 
     let entry = ManifestEntry {
-        dump_url: dump_url.to_string(),
-        dump_timestamp: timestamp.to_rfc3339(),
+        dump_url: update.dump_url.clone(),
+        dump_timestamp: update.dump_timestamp.to_rfc3339(),
         seed_generation,
+        dump_sha256: update.dump_sha256.clone(),
+        cli_version: update.cli_version.clone(),
     };
     manifest.networks.insert(network_id, entry);
 
     write_manifest(manifest_path, &manifest)
 }
 
-fn load_manifest(manifest_path: &Path) -> Result<Manifest> {
+pub fn load_manifest(manifest_path: &Path) -> Result<Manifest> {
     if !manifest_path.exists() {
         return Ok(Manifest::new());
     }
@@ -280,9 +290,13 @@ mod tests {
 
         update_manifest(
             &manifest_path,
-            42161,
-            "https://example.com/42161.sql.gz",
-            Utc::now(),
+            &ManifestEntryUpdate {
+                chain_id: 42161,
+                dump_url: "https://example.com/42161.sql.gz".to_string(),
+                dump_timestamp: Utc::now(),
+                dump_sha256: None,
+                cli_version: None,
+            },
         )
         .unwrap();
 
@@ -310,15 +324,21 @@ mod tests {
                 dump_url: "https://example.com/old.sql.gz".to_string(),
                 dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
                 seed_generation: 3,
+                dump_sha256: None,
+                cli_version: None,
             },
         );
         fs::write(&manifest_path, serde_yaml::to_string(&manifest).unwrap()).unwrap();
 
         update_manifest(
             &manifest_path,
-            42161,
-            "https://example.com/new.sql.gz",
-            Utc::now(),
+            &ManifestEntryUpdate {
+                chain_id: 42161,
+                dump_url: "https://example.com/new.sql.gz".to_string(),
+                dump_timestamp: Utc::now(),
+                dump_sha256: None,
+                cli_version: None,
+            },
         )
         .unwrap();
 
@@ -344,9 +364,13 @@ mod tests {
 
         let err = update_manifest(
             &manifest_path,
-            1,
-            "https://example.com/1.sql.gz",
-            Utc::now(),
+            &ManifestEntryUpdate {
+                chain_id: 1,
+                dump_url: "https://example.com/1.sql.gz".to_string(),
+                dump_timestamp: Utc::now(),
+                dump_sha256: None,
+                cli_version: None,
+            },
         )
         .unwrap_err();
 
@@ -400,6 +424,8 @@ mod tests {
                 dump_url: "https://example.com/10.sql.gz".to_string(),
                 dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
                 seed_generation: 5,
+                dump_sha256: None,
+                cli_version: None,
             },
         );
         write_manifest(&manifest_path, &manifest).unwrap();
@@ -0,0 +1,450 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+
+use crate::http::redact_url;
+use crate::report::RunReport;
+
+const METRIC_PREFIX: &str = "rain_local_db";
+const FAILURES_METRIC: &str = "rain_local_db_sync_failures_total";
+
+pub fn write_textfile(path: &Path, report: &RunReport, now: DateTime<Utc>) -> Result<()> {
+    let previous_failures = match fs::read_to_string(path) {
+        Ok(contents) => parse_failure_counts(&contents),
+        Err(_) => BTreeMap::new(),
+    };
+    let contents = render_textfile(report, now, &previous_failures);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create metrics directory {}", parent.display()))?;
+    }
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow::anyhow!("metrics path is missing a valid filename"))?;
+    let temp_path = path.with_file_name(format!("{file_name}.tmp"));
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("failed to write metrics to {}", temp_path.display()))?;
+    fs::rename(&temp_path, path).with_context(|| {
+        format!(
+            "failed to move metrics {} to {}",
+            temp_path.display(),
+            path.display()
+        )
+    })?;
+    Ok(())
+}
+
+fn render_textfile(
+    report: &RunReport,
+    now: DateTime<Utc>,
+    previous_failures: &BTreeMap<u64, u64>,
+) -> String {
+    let mut out = String::new();
+
+    let finished_at = report.finished_at.unwrap_or(now);
+    let duration = finished_at - report.started_at;
+    family(
+        &mut out,
+        "run_duration_seconds",
+        "gauge",
+        "Wall-clock duration of the last sync run.",
+    );
+    let _ = writeln!(
+        out,
+        "{METRIC_PREFIX}_run_duration_seconds {}",
+        duration.num_milliseconds() as f64 / 1000.0
+    );
+
+    family(
+        &mut out,
+        "last_run_success",
+        "gauge",
+        "Whether the last sync run completed without errors (1) or not (0).",
+    );
+    let _ = writeln!(
+        out,
+        "{METRIC_PREFIX}_last_run_success {}",
+        u8::from(report.succeeded())
+    );
+
+    family(
+        &mut out,
+        "last_run_timestamp_seconds",
+        "gauge",
+        "Unix time at which the last sync run finished.",
+    );
+    let _ = writeln!(
+        out,
+        "{METRIC_PREFIX}_last_run_timestamp_seconds {}",
+        finished_at.timestamp()
+    );
+
+    family(
+        &mut out,
+        "last_synced_block",
+        "gauge",
+        "Last block synced per chain/orderbook, or per chain from sync_status when no watermarks exist.",
+    );
+    for chain in &report.chains {
+        if !chain.orderbooks.is_empty() {
+            for orderbook in &chain.orderbooks {
+                let _ = writeln!(
+                    out,
+                    "{METRIC_PREFIX}_last_synced_block{{chain_id=\"{}\",orderbook=\"{}\"}} {}",
+                    chain.chain_id,
+                    escape_label(&orderbook.orderbook),
+                    orderbook.last_block
+                );
+            }
+        } else if let Some(block) = chain.last_synced_block {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_last_synced_block{{chain_id=\"{}\"}} {block}",
+                chain.chain_id
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "end_block_time_ms",
+        "gauge",
+        "Unix time in milliseconds of the newest block indexed in the synced database per chain/orderbook.",
+    );
+    for chain in &report.chains {
+        for orderbook in &chain.orderbooks {
+            if let Some(end_block_time_ms) = orderbook.end_block_time_ms {
+                let _ = writeln!(
+                    out,
+                    "{METRIC_PREFIX}_end_block_time_ms{{chain_id=\"{}\",orderbook=\"{}\"}} {end_block_time_ms}",
+                    chain.chain_id,
+                    escape_label(&orderbook.orderbook)
+                );
+            }
+        }
+    }
+
+    family(
+        &mut out,
+        "end_block_lag_ms",
+        "gauge",
+        "Milliseconds between end_block_time_ms and the time metrics were written.",
+    );
+    for chain in &report.chains {
+        for orderbook in &chain.orderbooks {
+            if let Some(end_block_time_ms) = orderbook.end_block_time_ms {
+                let _ = writeln!(
+                    out,
+                    "{METRIC_PREFIX}_end_block_lag_ms{{chain_id=\"{}\",orderbook=\"{}\"}} {}",
+                    chain.chain_id,
+                    escape_label(&orderbook.orderbook),
+                    (now.timestamp_millis() - end_block_time_ms).max(0)
+                );
+            }
+        }
+    }
+
+    family(
+        &mut out,
+        "last_sync_completed_ms",
+        "gauge",
+        "Unix time in milliseconds at which the chain dump was last synced successfully.",
+    );
+    for chain in &report.chains {
+        if let Some(last_sync_completed) = chain.last_sync_completed {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_last_sync_completed_ms{{chain_id=\"{}\"}} {}",
+                chain.chain_id,
+                last_sync_completed.timestamp_millis()
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "chain_sync_duration_seconds",
+        "gauge",
+        "Duration of the last successful sync per chain.",
+    );
+    for chain in &report.chains {
+        if let Some(duration_ms) = chain.duration_ms {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_chain_sync_duration_seconds{{chain_id=\"{}\"}} {}",
+                chain.chain_id,
+                duration_ms as f64 / 1000.0
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "dump_bytes",
+        "gauge",
+        "Size of the compressed SQL dump per chain.",
+    );
+    for chain in &report.chains {
+        if let Some(bytes) = chain.dump_bytes {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_dump_bytes{{chain_id=\"{}\"}} {bytes}",
+                chain.chain_id
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "table_rows",
+        "gauge",
+        "Row count per table in the synced database.",
+    );
+    for chain in &report.chains {
+        for (table, rows) in &chain.table_rows {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_table_rows{{chain_id=\"{}\",table=\"{}\"}} {rows}",
+                chain.chain_id,
+                escape_label(table)
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "cli_timed_out",
+        "gauge",
+        "Whether the CLI was killed for exceeding its timeout (1) or not (0) per chain.",
+    );
+    for chain in &report.chains {
+        let _ = writeln!(
+            out,
+            "{METRIC_PREFIX}_cli_timed_out{{chain_id=\"{}\"}} {}",
+            chain.chain_id,
+            u8::from(chain.timed_out)
+        );
+    }
+
+    family(
+        &mut out,
+        "cli_batches_fetched",
+        "gauge",
+        "Batches the CLI reported fetching during the last sync per chain.",
+    );
+    for chain in &report.chains {
+        if let Some(count) = chain.batches_fetched() {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_cli_batches_fetched{{chain_id=\"{}\"}} {count}",
+                chain.chain_id
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "cli_last_processed_block",
+        "gauge",
+        "Highest block the CLI reported processing during the last sync per chain.",
+    );
+    for chain in &report.chains {
+        if let Some(block) = chain.last_processed_block() {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_cli_last_processed_block{{chain_id=\"{}\"}} {block}",
+                chain.chain_id
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "artifact_source_info",
+        "gauge",
+        "Source URL that served each downloaded artifact in the last run.",
+    );
+    for source in &report.artifact_sources {
+        let _ = writeln!(
+            out,
+            "{METRIC_PREFIX}_artifact_source_info{{artifact=\"{}\",url=\"{}\"}} 1",
+            escape_label(&source.artifact),
+            escape_label(&redact_url(&source.url))
+        );
+    }
+
+    if let Some(version) = &report.cli_version {
+        family(
+            &mut out,
+            "cli_info",
+            "gauge",
+            "Version of rain-orderbook-cli used in the last run.",
+        );
+        let _ = writeln!(
+            out,
+            "{METRIC_PREFIX}_cli_info{{version=\"{}\"}} 1",
+            escape_label(version)
+        );
+    }
+
+    let mut failures = previous_failures.clone();
+    for chain in &report.chains {
+        let count = failures.entry(chain.chain_id).or_insert(0);
+        if !chain.succeeded {
+            *count += 1;
+        }
+    }
+    family(
+        &mut out,
+        "sync_failures_total",
+        "counter",
+        "Number of failed sync attempts per chain across runs.",
+    );
+    for (chain_id, count) in &failures {
+        let _ = writeln!(out, "{FAILURES_METRIC}{{chain_id=\"{chain_id}\"}} {count}");
+    }
+
+    out
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {METRIC_PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_{name} {kind}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn parse_failure_counts(contents: &str) -> BTreeMap<u64, u64> {
+    let prefix = format!("{FAILURES_METRIC}{{chain_id=\"");
+    contents
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix(&prefix)?;
+            let (chain_id, rest) = rest.split_once("\"}")?;
+            let chain_id = chain_id.parse::<u64>().ok()?;
+            let count = rest.trim().parse::<u64>().ok()?;
+            Some((chain_id, count))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::OrderbookProgress;
+    use crate::report::{ArtifactSource, ChainReport, CliProgressEvent};
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn sample_report() -> RunReport {
+        let started_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut report = RunReport::new(started_at);
+        report.finished_at = Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap());
+        report.chains.push(ChainReport {
+            chain_id: 42161,
+            succeeded: true,
+            timed_out: false,
+            duration_ms: Some(20_000),
+            last_synced_block: Some(1_000),
+            last_sync_completed: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 20).unwrap()),
+            dump_bytes: Some(2_048),
+            table_rows: [("orders".to_string(), 12)].into_iter().collect(),
+            orderbooks: vec![OrderbookProgress {
+                orderbook: "0xabc".to_string(),
+                last_block: 1_000,
+                end_block_time_ms: Some(1_704_067_150_000),
+            }],
+            cli_events: vec![
+                CliProgressEvent::BatchesFetched { count: 3 },
+                CliProgressEvent::BlocksProcessed { block: 900 },
+                CliProgressEvent::BatchesFetched { count: 2 },
+                CliProgressEvent::BlocksProcessed { block: 1_000 },
+            ],
+        });
+        report.chains.push(ChainReport {
+            timed_out: true,
+            ..ChainReport::failed(
+                137,
+                Some(Utc.with_ymd_and_hms(2023, 12, 31, 0, 0, 0).unwrap()),
+            )
+        });
+        report.artifact_sources.push(ArtifactSource {
+            artifact: "42161.sql.gz".to_string(),
+            url: "https://mirror.example/42161.sql.gz?token=abc".to_string(),
+        });
+        report
+    }
+
+    #[test]
+    fn render_textfile_emits_expected_series() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 0).unwrap();
+        let output = render_textfile(&sample_report(), now, &BTreeMap::new());
+
+        assert!(output.contains("rain_local_db_run_duration_seconds 30\n"));
+        assert!(output.contains("rain_local_db_last_run_success 0\n"));
+        assert!(output.contains(
+            "rain_local_db_last_synced_block{chain_id=\"42161\",orderbook=\"0xabc\"} 1000\n"
+        ));
+        assert!(output.contains(
+            "rain_local_db_end_block_time_ms{chain_id=\"42161\",orderbook=\"0xabc\"} 1704067150000\n"
+        ));
+        assert!(output.contains(
+            "rain_local_db_end_block_lag_ms{chain_id=\"42161\",orderbook=\"0xabc\"} 110000\n"
+        ));
+        assert!(!output.contains("rain_local_db_end_block_lag_ms{chain_id=\"137\""));
+        assert!(output
+            .contains("rain_local_db_last_sync_completed_ms{chain_id=\"137\"} 1703980800000\n"));
+        assert!(output.contains("rain_local_db_dump_bytes{chain_id=\"42161\"} 2048\n"));
+        assert!(
+            output.contains("rain_local_db_table_rows{chain_id=\"42161\",table=\"orders\"} 12\n")
+        );
+        assert!(output.contains("rain_local_db_cli_batches_fetched{chain_id=\"42161\"} 5\n"));
+        assert!(
+            output.contains("rain_local_db_cli_last_processed_block{chain_id=\"42161\"} 1000\n")
+        );
+        assert!(!output.contains("rain_local_db_cli_batches_fetched{chain_id=\"137\"}"));
+        assert!(output.contains("rain_local_db_cli_timed_out{chain_id=\"42161\"} 0\n"));
+        assert!(output.contains("rain_local_db_cli_timed_out{chain_id=\"137\"} 1\n"));
+        assert!(output.contains("rain_local_db_sync_failures_total{chain_id=\"42161\"} 0\n"));
+        assert!(output.contains("rain_local_db_sync_failures_total{chain_id=\"137\"} 1\n"));
+        assert!(output.contains("# TYPE rain_local_db_sync_failures_total counter"));
+        assert!(output.contains(
+            "rain_local_db_artifact_source_info{artifact=\"42161.sql.gz\",url=\"https://mirror.example/42161.sql.gz?token=***\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn write_textfile_accumulates_failure_counts_across_runs() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("metrics/rain_local_db.prom");
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 0).unwrap();
+
+        write_textfile(&path, &sample_report(), now).unwrap();
+        write_textfile(&path, &sample_report(), now).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("rain_local_db_sync_failures_total{chain_id=\"137\"} 2\n"));
+        assert!(contents.contains("rain_local_db_sync_failures_total{chain_id=\"42161\"} 0\n"));
+        assert!(!path.with_file_name("rain_local_db.prom.tmp").exists());
+    }
+
+    #[test]
+    fn parse_failure_counts_ignores_unrelated_lines() {
+        let contents = "# TYPE rain_local_db_sync_failures_total counter\n\
+rain_local_db_sync_failures_total{chain_id=\"10\"} 3\n\
+rain_local_db_dump_bytes{chain_id=\"10\"} 5\n";
+        let counts = parse_failure_counts(contents);
+        assert_eq!(counts, [(10, 3)].into_iter().collect());
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::blocking::{Body, Client};
+use sha2::{Digest, Sha256};
+
+use crate::constants::{
+    PUBLISH_DIR_ENV_VAR, PUBLISH_S3_ACCESS_KEY_ENV_VAR, PUBLISH_S3_BUCKET_ENV_VAR,
+    PUBLISH_S3_ENDPOINT_ENV_VAR, PUBLISH_S3_PREFIX_ENV_VAR, PUBLISH_S3_REGION_ENV_VAR,
+    PUBLISH_S3_SECRET_KEY_ENV_VAR,
+};
+use crate::http::{send_with_retries, HttpClientConfig};
+use crate::sync::Publisher;
+use crate::verify::sha256_file;
+
+const DEFAULT_S3_REGION: &str = "us-east-1";
+
+#[derive(Clone, Debug)]
+pub struct LocalDirectoryPublisher {
+    pub dir: PathBuf,
+}
+
+impl LocalDirectoryPublisher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl Publisher for LocalDirectoryPublisher {
+    fn publish(&self, source: &Path, name: &str) -> Result<String> {
+        let destination = self.dir.join(name);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create publish directory {}", parent.display())
+            })?;
+        }
+        let file_name = destination
+            .file_name()
+            .with_context(|| format!("invalid publish name {name}"))?;
+        let temp_path =
+            destination.with_file_name(format!("{}.partial", file_name.to_string_lossy()));
+        fs::copy(source, &temp_path).with_context(|| {
+            format!(
+                "failed to copy {} to {}",
+                source.display(),
+                temp_path.display()
+            )
+        })?;
+        fs::rename(&temp_path, &destination).with_context(|| {
+            format!(
+                "failed to move {} to {}",
+                temp_path.display(),
+                destination.display()
+            )
+        })?;
+        Ok(destination.display().to_string())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct S3Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct S3Publisher {
+    endpoint: String,
+    bucket: String,
+    region: String,
+    prefix: String,
+    credentials: S3Credentials,
+    client: Client,
+    http: HttpClientConfig,
+}
+
+impl S3Publisher {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        prefix: &str,
+        credentials: S3Credentials,
+        http: &HttpClientConfig,
+    ) -> Result<Self> {
+        let builder = Client::builder()
+            .user_agent("rain-local-db-sync/1.0")
+            .connect_timeout(http.connect_timeout)
+            .timeout(None);
+        #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+        let builder = builder.tcp_user_timeout(http.read_timeout);
+        let client = builder
+            .build()
+            .context("failed to construct S3 HTTP client")?;
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            region: region.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            credentials,
+            client,
+            http: http.clone(),
+        })
+    }
+
+    fn object_key(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{name}", self.prefix)
+        }
+    }
+}
+
+impl Publisher for S3Publisher {
+    fn publish(&self, source: &Path, name: &str) -> Result<String> {
+        let size = fs::metadata(source)
+            .with_context(|| format!("failed to read {}", source.display()))?
+            .len();
+        let payload_hash = sha256_file(source)?;
+        let key = self.object_key(name);
+        let url = format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.bucket,
+            uri_encode_path(&key)
+        );
+        let parsed =
+            reqwest::Url::parse(&url).with_context(|| format!("invalid S3 object URL {url}"))?;
+        let host = match parsed.port() {
+            Some(port) => format!("{}:{port}", parsed.host_str().unwrap_or_default()),
+            None => parsed.host_str().unwrap_or_default().to_string(),
+        };
+
+        send_with_retries(
+            &self.http,
+            &url,
+            || {
+                let file = fs::File::open(source)
+                    .with_context(|| format!("failed to open {}", source.display()))?;
+                let signed = sign_put_request(
+                    &self.credentials,
+                    &self.region,
+                    &host,
+                    parsed.path(),
+                    &payload_hash,
+                    Utc::now(),
+                );
+                Ok(self
+                    .client
+                    .put(parsed.clone())
+                    .header("x-amz-date", signed.amz_date)
+                    .header("x-amz-content-sha256", &payload_hash)
+                    .header("authorization", signed.authorization)
+                    .body(Body::sized(file, size)))
+            },
+            |_| Ok(()),
+        )?;
+        Ok(url)
+    }
+}
+
+pub fn publisher_from_env(
+    env: &HashMap<String, String>,
+    http: &HttpClientConfig,
+) -> Result<Option<Box<dyn Publisher>>> {
+    let value = |key: &str| {
+        env.get(key)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+
+    let directory = value(PUBLISH_DIR_ENV_VAR);
+    let bucket = value(PUBLISH_S3_BUCKET_ENV_VAR);
+    match (directory, bucket) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("set only one of {PUBLISH_DIR_ENV_VAR} and {PUBLISH_S3_BUCKET_ENV_VAR}")
+        }
+        (Some(directory), None) => {
+            println!("Publishing artifacts to directory {directory}");
+            Ok(Some(Box::new(LocalDirectoryPublisher::new(directory))))
+        }
+        (None, Some(bucket)) => {
+            let endpoint = value(PUBLISH_S3_ENDPOINT_ENV_VAR).with_context(|| {
+                format!("{PUBLISH_S3_ENDPOINT_ENV_VAR} must be set when publishing to S3")
+            })?;
+            let credentials = S3Credentials {
+                access_key_id: value(PUBLISH_S3_ACCESS_KEY_ENV_VAR)
+                    .with_context(|| {
+                        format!("{PUBLISH_S3_ACCESS_KEY_ENV_VAR} must be set when publishing to S3")
+                    })?
+                    .to_string(),
+                secret_access_key: value(PUBLISH_S3_SECRET_KEY_ENV_VAR)
+                    .with_context(|| {
+                        format!("{PUBLISH_S3_SECRET_KEY_ENV_VAR} must be set when publishing to S3")
+                    })?
+                    .to_string(),
+            };
+            let region = value(PUBLISH_S3_REGION_ENV_VAR).unwrap_or(DEFAULT_S3_REGION);
+            let prefix = value(PUBLISH_S3_PREFIX_ENV_VAR).unwrap_or_default();
+            println!("Publishing artifacts to s3://{bucket}/{prefix} via {endpoint}");
+            let publisher = S3Publisher::new(endpoint, bucket, region, prefix, credentials, http)?;
+            Ok(Some(Box::new(publisher)))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+struct SignedRequest {
+    amz_date: String,
+    authorization: String,
+}
+
+fn sign_put_request(
+    credentials: &S3Credentials,
+    region: &str,
+    host: &str,
+    path: &str,
+    payload_hash: &str,
+    now: DateTime<Utc>,
+) -> SignedRequest {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date_stamp = now.format("%Y%m%d").to_string();
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{path}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+    );
+    let scope = format!("{date_stamp}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = signing_key(&credentials.secret_access_key, &date_stamp, region, "s3");
+    let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    );
+
+    SignedRequest {
+        amz_date,
+        authorization,
+    }
+}
+
+fn signing_key(secret: &str, date_stamp: &str, region: &str, service: &str) -> Vec<u8> {
+    let date_key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date_stamp.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, service.as_bytes());
+    hmac_sha256(&service_key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
+
+fn uri_encode_path(key: &str) -> String {
+    let mut encoded = String::new();
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httptest::matchers::*;
+    use httptest::responders::*;
+    use httptest::{cycle, Expectation, Server};
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[test]
+    fn local_directory_publisher_copies_artifact() {
+        let source_dir = tempdir().unwrap();
+        let source = source_dir.path().join("10.sql.gz");
+        fs::write(&source, b"dump").unwrap();
+        let target = tempdir().unwrap();
+
+        let publisher = LocalDirectoryPublisher::new(target.path().join("mirror"));
+        let location = publisher.publish(&source, "10.sql.gz").unwrap();
+
+        let published = target.path().join("mirror/10.sql.gz");
+        assert_eq!(location, published.display().to_string());
+        assert_eq!(fs::read(&published).unwrap(), b"dump");
+        assert!(!target.path().join("mirror/10.sql.gz.partial").exists());
+    }
+
+    #[test]
+    fn local_directory_publisher_creates_nested_object_names() {
+        let source_dir = tempdir().unwrap();
+        let source = source_dir.path().join("10.sql.gz");
+        fs::write(&source, b"dump").unwrap();
+        let target = tempdir().unwrap();
+
+        let publisher = LocalDirectoryPublisher::new(target.path());
+        let location = publisher.publish(&source, "sync-42/10.sql.gz").unwrap();
+
+        let published = target.path().join("sync-42/10.sql.gz");
+        assert_eq!(location, published.display().to_string());
+        assert_eq!(fs::read(&published).unwrap(), b"dump");
+        assert!(!target.path().join("sync-42/10.sql.gz.partial").exists());
+        assert!(!target.path().join("sync-42/sync-42").exists());
+    }
+
+    #[test]
+    fn signing_key_matches_aws_reference_vector() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn s3_publisher_puts_signed_object() {
+        let server = Server::run();
+        let payload_hash = hex(&Sha256::digest(b"dump-bytes"));
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/dumps/staging/10.sql.gz"),
+                request::headers(contains(("x-amz-content-sha256", payload_hash))),
+                request::headers(contains((
+                    "authorization",
+                    matches("^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/[0-9]{8}/auto/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature=[0-9a-f]{64}$")
+                ))),
+                request::body("dump-bytes"),
+            ])
+            .respond_with(status_code(200)),
+        );
+
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("10.sql.gz");
+        fs::write(&source, b"dump-bytes").unwrap();
+
+        let publisher = S3Publisher::new(
+            &server.url_str(""),
+            "dumps",
+            "auto",
+            "/staging/",
+            S3Credentials {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                secret_access_key: "secret".to_string(),
+            },
+            &fast_retry_config(),
+        )
+        .unwrap();
+        let location = publisher.publish(&source, "10.sql.gz").unwrap();
+        assert!(location.ends_with("/dumps/staging/10.sql.gz"));
+    }
+
+    #[test]
+    fn s3_publisher_reports_upload_failures() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("PUT", "/dumps/manifest.yaml"))
+                .respond_with(status_code(403).body("AccessDenied")),
+        );
+
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("manifest.yaml");
+        fs::write(&source, b"networks: {}").unwrap();
+
+        let publisher = S3Publisher::new(
+            &server.url_str(""),
+            "dumps",
+            "auto",
+            "",
+            S3Credentials {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                secret_access_key: "secret".to_string(),
+            },
+            &fast_retry_config(),
+        )
+        .unwrap();
+        let err = publisher.publish(&source, "manifest.yaml").unwrap_err();
+        assert!(err.to_string().contains("403"));
+        assert!(err.to_string().contains("AccessDenied"));
+    }
+
+    #[test]
+    fn s3_publisher_retries_server_errors() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("PUT", "/dumps/10.sql.gz"),
+                request::body("dump-bytes"),
+            ])
+            .times(2)
+            .respond_with(cycle![status_code(503), status_code(200)]),
+        );
+
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("10.sql.gz");
+        fs::write(&source, b"dump-bytes").unwrap();
+
+        let publisher = S3Publisher::new(
+            &server.url_str(""),
+            "dumps",
+            "auto",
+            "",
+            S3Credentials {
+                access_key_id: "AKIDEXAMPLE".to_string(),
+                secret_access_key: "secret".to_string(),
+            },
+            &fast_retry_config(),
+        )
+        .unwrap();
+        publisher.publish(&source, "10.sql.gz").unwrap();
+    }
+
+    fn fast_retry_config() -> HttpClientConfig {
+        HttpClientConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..HttpClientConfig::default()
+        }
+    }
+
+    #[test]
+    fn publisher_from_env_requires_complete_s3_settings() {
+        let mut env = HashMap::new();
+        env.insert(PUBLISH_S3_BUCKET_ENV_VAR.to_string(), "dumps".to_string());
+        let err = publisher_from_env(&env, &HttpClientConfig::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains(PUBLISH_S3_ENDPOINT_ENV_VAR));
+
+        env.insert(PUBLISH_DIR_ENV_VAR.to_string(), "out".to_string());
+        let err = publisher_from_env(&env, &HttpClientConfig::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("set only one of"));
+
+        assert!(
+            publisher_from_env(&HashMap::new(), &HttpClientConfig::default())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+
+use crate::constants::{DEFAULT_RELEASE_TAG, RELEASE_DOWNLOAD_URL_TEMPLATE};
+
+const LATEST_DOWNLOAD_SEGMENT: &str = "/releases/latest/download/";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleaseLocation {
+    pub template: String,
+    pub tag: String,
+    pub orderbooks: BTreeMap<u64, String>,
+    pub mirrors: Vec<String>,
+}
+
+impl Default for ReleaseLocation {
+    fn default() -> Self {
+        Self::new(RELEASE_DOWNLOAD_URL_TEMPLATE)
+    }
+}
+
+impl ReleaseLocation {
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: file_template(template),
+            tag: DEFAULT_RELEASE_TAG.to_string(),
+            orderbooks: BTreeMap::new(),
+            mirrors: Vec::new(),
+        }
+    }
+
+    pub fn with_mirrors(mut self, mirrors: &[String]) -> Self {
+        self.mirrors = mirrors.iter().map(|mirror| file_template(mirror)).collect();
+        self
+    }
+
+    pub fn pinned(&self, tag: &str) -> Result<Self> {
+        let template = if self.template.contains("{tag}") {
+            self.template.clone()
+        } else if self.template.contains(LATEST_DOWNLOAD_SEGMENT) {
+            self.template
+                .replace(LATEST_DOWNLOAD_SEGMENT, "/releases/download/{tag}/")
+        } else {
+            anyhow::bail!(
+                "cannot pin release URL template {} to tag {tag}: it has no {{tag}} placeholder",
+                self.template
+            );
+        };
+        Ok(Self {
+            template,
+            tag: tag.to_string(),
+            orderbooks: self.orderbooks.clone(),
+            mirrors: Vec::new(),
+        })
+    }
+
+    pub fn manifest_url(&self) -> String {
+        self.render_manifest(&self.template)
+    }
+
+    pub fn manifest_urls(&self) -> Vec<String> {
+        self.templates()
+            .map(|template| self.render_manifest(template))
+            .collect()
+    }
+
+    pub fn dump_url(&self, chain_id: u64, file_name: &str) -> Result<String> {
+        self.render_dump(&self.template, chain_id, file_name)
+    }
+
+    pub fn dump_urls(&self, chain_id: u64, file_name: &str) -> Result<Vec<String>> {
+        self.templates()
+            .map(|template| self.render_dump(template, chain_id, file_name))
+            .collect()
+    }
+
+    pub fn manifest_object_name(&self) -> String {
+        object_name(&self.template, &self.manifest_url())
+    }
+
+    pub fn dump_object_name(&self, chain_id: u64, file_name: &str) -> Result<String> {
+        Ok(object_name(
+            &self.template,
+            &self.dump_url(chain_id, file_name)?,
+        ))
+    }
+
+    pub fn uses_orderbook(&self) -> bool {
+        self.templates()
+            .any(|template| template.contains("{orderbook}"))
+    }
+
+    fn templates(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.template).chain(&self.mirrors)
+    }
+
+    fn render_dump(&self, template: &str, chain_id: u64, file_name: &str) -> Result<String> {
+        let orderbook = match self.orderbooks.get(&chain_id) {
+            Some(orderbook) => orderbook.as_str(),
+            None if template.contains("{orderbook}") => anyhow::bail!(
+                "release URL template {template} uses {{orderbook}} but no orderbook is known for chain {chain_id}"
+            ),
+            None => "",
+        };
+        Ok(self.render(template, &chain_id.to_string(), orderbook, file_name))
+    }
+
+    // The manifest is shared by every chain, so per-chain placeholders render empty and
+    // the path segments they occupied are dropped rather than left as `//`.
+    fn render_manifest(&self, template: &str) -> String {
+        collapse_empty_segments(&self.render(template, "", "", "manifest.yaml"))
+    }
+
+    fn render(&self, template: &str, chain: &str, orderbook: &str, file_name: &str) -> String {
+        template
+            .replace("{tag}", &self.tag)
+            .replace("{chain}", chain)
+            .replace("{orderbook}", orderbook)
+            .replace("{file}", file_name)
+    }
+}
+
+fn object_name(template: &str, url: &str) -> String {
+    let fixed = &template[..template.find('{').unwrap_or(template.len())];
+    let base = &fixed[..fixed.rfind('/').map_or(0, |index| index + 1)];
+    url.strip_prefix(base).unwrap_or(url).to_string()
+}
+
+fn collapse_empty_segments(url: &str) -> String {
+    let (scheme, rest) = match url.find("://") {
+        Some(index) => url.split_at(index + 3),
+        None => ("", url),
+    };
+    let (path, query) = rest.split_at(rest.find('?').unwrap_or(rest.len()));
+    let mut collapsed = String::with_capacity(url.len());
+    collapsed.push_str(scheme);
+    for c in path.chars() {
+        if c == '/' && collapsed.ends_with('/') && collapsed.len() > scheme.len() {
+            continue;
+        }
+        collapsed.push(c);
+    }
+    collapsed.push_str(query);
+    collapsed
+}
+
+fn file_template(template: &str) -> String {
+    if template.contains("{file}") {
+        template.to_string()
+    } else {
+        format!("{}/{{file}}", template.trim_end_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_url_substitutes_all_placeholders() {
+        let mut location =
+            ReleaseLocation::new("https://cdn.example/{tag}/{chain}/{orderbook}/{file}");
+        location.tag = "v1.2.0".to_string();
+        location.orderbooks.insert(10, "0xabc".to_string());
+
+        assert_eq!(
+            location.dump_url(10, "10.sql.gz").unwrap(),
+            "https://cdn.example/v1.2.0/10/0xabc/10.sql.gz"
+        );
+    }
+
+    #[test]
+    fn manifest_url_leaves_chain_placeholders_empty() {
+        let location = ReleaseLocation::new("https://cdn.example/{tag}/{file}");
+        assert_eq!(
+            location.manifest_url(),
+            "https://cdn.example/latest/manifest.yaml"
+        );
+        assert_eq!(
+            ReleaseLocation::default().manifest_url(),
+            RELEASE_DOWNLOAD_URL_TEMPLATE.replace("{file}", "manifest.yaml")
+        );
+    }
+
+    #[test]
+    fn manifest_url_drops_segments_of_per_chain_placeholders() {
+        let location =
+            ReleaseLocation::new("https://cdn.example/dumps/{chain}/{orderbook}/{tag}/{file}");
+        assert_eq!(
+            location.manifest_url(),
+            "https://cdn.example/dumps/latest/manifest.yaml"
+        );
+        assert_eq!(location.manifest_object_name(), "latest/manifest.yaml");
+        assert_eq!(
+            ReleaseLocation::new("https://cdn.example/{chain}/{file}?next=https://x//y")
+                .manifest_url(),
+            "https://cdn.example/manifest.yaml?next=https://x//y"
+        );
+        assert_eq!(
+            ReleaseLocation::new("file:///srv/{chain}/{file}").manifest_url(),
+            "file:///srv/manifest.yaml"
+        );
+    }
+
+    #[test]
+    fn pinned_rewrites_latest_github_download_urls() {
+        let pinned = ReleaseLocation::default().pinned("sync-42").unwrap();
+        assert_eq!(
+            pinned.dump_url(10, "10.sql.gz").unwrap(),
+            "https://github.com/findolor/local_db_remote/releases/download/sync-42/10.sql.gz"
+        );
+    }
+
+    #[test]
+    fn pinned_substitutes_tag_placeholder() {
+        let pinned = ReleaseLocation::new("https://cdn.example/{tag}/{file}")
+            .pinned("v3")
+            .unwrap();
+        assert_eq!(
+            pinned.dump_url(1, "1.sql.gz").unwrap(),
+            "https://cdn.example/v3/1.sql.gz"
+        );
+    }
+
+    #[test]
+    fn object_names_are_relative_to_the_template_base() {
+        let location = ReleaseLocation::new("https://cdn.example/dumps/{tag}/{chain}/{file}")
+            .pinned("v3")
+            .unwrap();
+        assert_eq!(
+            location.dump_object_name(10, "10.sql.gz").unwrap(),
+            "v3/10/10.sql.gz"
+        );
+        assert_eq!(
+            ReleaseLocation::new("https://cdn.example/dumps/{tag}/{file}").manifest_object_name(),
+            "latest/manifest.yaml"
+        );
+
+        let pinned = ReleaseLocation::default().pinned("sync-42").unwrap();
+        assert_eq!(
+            pinned.dump_object_name(10, "10.sql.gz").unwrap(),
+            "sync-42/10.sql.gz"
+        );
+        assert_eq!(
+            ReleaseLocation::default().manifest_object_name(),
+            "manifest.yaml"
+        );
+    }
+
+    #[test]
+    fn pinned_errors_when_template_cannot_carry_a_tag() {
+        let err = ReleaseLocation::new("https://cdn.example/latest/{file}")
+            .pinned("v3")
+            .unwrap_err();
+        assert!(err.to_string().contains("has no {tag} placeholder"));
+    }
+
+    #[test]
+    fn template_without_file_placeholder_is_treated_as_a_base() {
+        let location = ReleaseLocation::new("/srv/release/");
+        assert_eq!(location.manifest_url(), "/srv/release/manifest.yaml");
+        assert_eq!(
+            location.dump_url(1, "1.sql.gz").unwrap(),
+            "/srv/release/1.sql.gz"
+        );
+    }
+
+    #[test]
+    fn mirrors_follow_the_primary_template_in_order() {
+        let location =
+            ReleaseLocation::new("https://primary.example/{tag}/{file}").with_mirrors(&[
+                "https://mirror-a.example/{chain}/{file}".to_string(),
+                "/srv/mirror-b".to_string(),
+            ]);
+        assert_eq!(
+            location.dump_urls(5, "5.sql.gz").unwrap(),
+            vec![
+                "https://primary.example/latest/5.sql.gz",
+                "https://mirror-a.example/5/5.sql.gz",
+                "/srv/mirror-b/5.sql.gz",
+            ]
+        );
+        assert_eq!(
+            location.manifest_urls(),
+            vec![
+                "https://primary.example/latest/manifest.yaml",
+                "https://mirror-a.example/manifest.yaml",
+                "/srv/mirror-b/manifest.yaml",
+            ]
+        );
+        assert!(location.pinned("v1").unwrap().mirrors.is_empty());
+    }
+
+    #[test]
+    fn dump_url_errors_when_orderbook_is_unknown() {
+        let location = ReleaseLocation::new("https://cdn.example/{orderbook}/{file}");
+        let err = location.dump_url(137, "137.sql.gz").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("no orderbook is known for chain 137"));
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::database::OrderbookProgress;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactSource {
+    pub artifact: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub chains: Vec<ChainReport>,
+    pub artifact_sources: Vec<ArtifactSource>,
+    pub cli_version: Option<String>,
+    pub error: Option<String>,
+}
+
+impl RunReport {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            finished_at: None,
+            chains: Vec::new(),
+            artifact_sources: Vec::new(),
+            cli_version: None,
+            error: None,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && self.chains.iter().all(|chain| chain.succeeded)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChainReport {
+    pub chain_id: u64,
+    pub succeeded: bool,
+    pub timed_out: bool,
+    pub duration_ms: Option<i64>,
+    pub last_synced_block: Option<u64>,
+    pub last_sync_completed: Option<DateTime<Utc>>,
+    pub dump_bytes: Option<u64>,
+    pub table_rows: BTreeMap<String, u64>,
+    pub orderbooks: Vec<OrderbookProgress>,
+    pub cli_events: Vec<CliProgressEvent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliProgressEvent {
+    BatchesFetched { count: u64 },
+    BlocksProcessed { block: u64 },
+}
+
+impl ChainReport {
+    pub fn failed(chain_id: u64, last_sync_completed: Option<DateTime<Utc>>) -> Self {
+        Self {
+            chain_id,
+            succeeded: false,
+            last_sync_completed,
+            ..Default::default()
+        }
+    }
+
+    pub fn batches_fetched(&self) -> Option<u64> {
+        self.cli_events
+            .iter()
+            .filter_map(|event| match event {
+                CliProgressEvent::BatchesFetched { count } => Some(*count),
+                CliProgressEvent::BlocksProcessed { .. } => None,
+            })
+            .reduce(|total, count| total + count)
+    }
+
+    pub fn last_processed_block(&self) -> Option<u64> {
+        self.cli_events
+            .iter()
+            .filter_map(|event| match event {
+                CliProgressEvent::BlocksProcessed { block } => Some(*block),
+                CliProgressEvent::BatchesFetched { .. } => None,
+            })
+            .max()
+    }
+}
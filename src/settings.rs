@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalDbSyncTuning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_batches: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_attempts: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_delay_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootstrap_block_threshold: Option<u64>,
+}
+
+impl LocalDbSyncTuning {
+    pub fn is_empty(&self) -> bool {
+        self.options().is_empty()
+    }
+
+    pub fn options(&self) -> Vec<(&'static str, u64)> {
+        [
+            ("batch-size", self.batch_size),
+            ("max-concurrent-batches", self.max_concurrent_batches),
+            ("retry-attempts", self.retry_attempts),
+            ("rate-limit-delay-ms", self.rate_limit_delay_ms),
+            ("bootstrap-block-threshold", self.bootstrap_block_threshold),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (key, value)))
+        .collect()
+    }
+}
+
+pub fn apply_local_db_sync_tuning(
+    settings: &mut Value,
+    chain_id: u64,
+    tuning: &LocalDbSyncTuning,
+) -> Result<String> {
+    let network = network_for_chain_id(settings, chain_id)?;
+    for (key, value) in tuning.options() {
+        set_local_db_sync_option(settings, &network, key, value.into())?;
+    }
+    Ok(network)
+}
+
+pub fn parse_settings(settings_yaml: &str) -> Result<Value> {
+    serde_yaml::from_str(settings_yaml).context("failed to parse settings YAML")
+}
+
+pub fn chain_id_for_network(settings: &Value, network: &str) -> Result<u64> {
+    let network_entry = settings
+        .get("networks")
+        .and_then(|networks| networks.get(network))
+        .with_context(|| format!("network `{network}` not found in settings YAML"))?;
+    let chain_id = network_entry
+        .get("chain-id")
+        .with_context(|| format!("network `{network}` is missing a chain-id"))?;
+    value_as_u64(chain_id)
+        .with_context(|| format!("network `{network}` has an invalid chain-id: {chain_id:?}"))
+}
+
+pub fn chain_id_for_orderbook(settings: &Value, orderbook: &str) -> Result<u64> {
+    let orderbooks = settings
+        .get("orderbooks")
+        .and_then(Value::as_mapping)
+        .context("settings YAML does not define any orderbooks")?;
+
+    let entry = orderbooks
+        .iter()
+        .find(|(name, entry)| {
+            name.as_str() == Some(orderbook)
+                || entry
+                    .get("address")
+                    .and_then(Value::as_str)
+                    .is_some_and(|address| address.eq_ignore_ascii_case(orderbook))
+        })
+        .map(|(_, entry)| entry)
+        .with_context(|| format!("orderbook `{orderbook}` not found in settings YAML"))?;
+
+    let network = entry
+        .get("network")
+        .and_then(Value::as_str)
+        .with_context(|| format!("orderbook `{orderbook}` is missing a network"))?;
+    chain_id_for_network(settings, network)
+}
+
+pub fn orderbook_addresses_by_chain(settings: &Value) -> BTreeMap<u64, String> {
+    let mut addresses = BTreeMap::new();
+    let Some(orderbooks) = settings.get("orderbooks").and_then(Value::as_mapping) else {
+        return addresses;
+    };
+    for entry in orderbooks.values() {
+        let address = entry.get("address").and_then(Value::as_str);
+        let network = entry.get("network").and_then(Value::as_str);
+        if let (Some(address), Some(network)) = (address, network) {
+            if let Ok(chain_id) = chain_id_for_network(settings, network) {
+                addresses
+                    .entry(chain_id)
+                    .or_insert_with(|| address.to_string());
+            }
+        }
+    }
+    addresses
+}
+
+pub fn network_for_chain_id(settings: &Value, chain_id: u64) -> Result<String> {
+    settings
+        .get("networks")
+        .and_then(Value::as_mapping)
+        .and_then(|networks| {
+            networks.iter().find_map(|(name, entry)| {
+                let matches = entry.get("chain-id").and_then(value_as_u64) == Some(chain_id);
+                matches.then(|| name.as_str().map(str::to_string)).flatten()
+            })
+        })
+        .with_context(|| format!("no network with chain-id {chain_id} in settings YAML"))
+}
+
+pub fn set_local_db_sync_option(
+    settings: &mut Value,
+    network: &str,
+    key: &str,
+    value: Value,
+) -> Result<()> {
+    let root = settings
+        .as_mapping_mut()
+        .context("settings YAML must be a mapping")?;
+    let local_db_sync = root
+        .entry(Value::from("local-db-sync"))
+        .or_insert_with(|| Value::Mapping(Default::default()))
+        .as_mapping_mut()
+        .context("settings YAML `local-db-sync` must be a mapping")?;
+    let network_entry = local_db_sync
+        .entry(Value::from(network))
+        .or_insert_with(|| Value::Mapping(Default::default()))
+        .as_mapping_mut()
+        .with_context(|| format!("settings YAML `local-db-sync.{network}` must be a mapping"))?;
+    network_entry.insert(Value::from(key), value);
+    Ok(())
+}
+
+fn value_as_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: &str = r#"
+networks:
+  arbitrum:
+    chain-id: 42161
+  polygon:
+    chain-id: "137"
+orderbooks:
+  arbitrum:
+    address: 0x8df8075e4077dabf1e95f49059e4c1eea33094ab
+    network: arbitrum
+  polygon:
+    address: 0x8a3C8E610d827093F7437E0C45EFa648563c0dDA
+    network: polygon
+"#;
+
+    #[test]
+    fn chain_id_for_orderbook_resolves_by_name() {
+        let settings = parse_settings(SETTINGS).unwrap();
+        assert_eq!(
+            chain_id_for_orderbook(&settings, "arbitrum").unwrap(),
+            42161
+        );
+        assert_eq!(chain_id_for_orderbook(&settings, "polygon").unwrap(), 137);
+    }
+
+    #[test]
+    fn chain_id_for_orderbook_resolves_by_address_case_insensitively() {
+        let settings = parse_settings(SETTINGS).unwrap();
+        let chain_id =
+            chain_id_for_orderbook(&settings, "0x8A3C8E610D827093F7437E0C45EFA648563C0DDA")
+                .unwrap();
+        assert_eq!(chain_id, 137);
+    }
+
+    #[test]
+    fn apply_local_db_sync_tuning_merges_only_set_options() {
+        let mut settings = parse_settings(
+            "networks:\n  base:\n    chain-id: 8453\nlocal-db-sync:\n  base:\n    batch-size: 10\n    retry-attempts: 3\n",
+        )
+        .unwrap();
+        let tuning = LocalDbSyncTuning {
+            max_concurrent_batches: Some(2),
+            rate_limit_delay_ms: Some(250),
+            retry_attempts: Some(5),
+            ..LocalDbSyncTuning::default()
+        };
+
+        let network = apply_local_db_sync_tuning(&mut settings, 8453, &tuning).unwrap();
+
+        assert_eq!(network, "base");
+        let base = &settings["local-db-sync"]["base"];
+        assert_eq!(base["batch-size"], 10);
+        assert_eq!(base["max-concurrent-batches"], 2);
+        assert_eq!(base["retry-attempts"], 5);
+        assert_eq!(base["rate-limit-delay-ms"], 250);
+        assert!(base.get("bootstrap-block-threshold").is_none());
+        assert!(apply_local_db_sync_tuning(&mut settings, 1, &tuning).is_err());
+        assert!(LocalDbSyncTuning::default().is_empty());
+    }
+
+    #[test]
+    fn set_local_db_sync_option_overrides_network_value() {
+        let mut settings = parse_settings(
+            "networks:\n  base:\n    chain-id: 8453\nlocal-db-sync:\n  base:\n    batch-size: 10\n    max-concurrent-batches: 15\n",
+        )
+        .unwrap();
+        let network = network_for_chain_id(&settings, 8453).unwrap();
+        assert_eq!(network, "base");
+
+        set_local_db_sync_option(&mut settings, &network, "max-concurrent-batches", 4.into())
+            .unwrap();
+        set_local_db_sync_option(&mut settings, "polygon", "batch-size", 5.into()).unwrap();
+
+        assert_eq!(
+            settings["local-db-sync"]["base"]["max-concurrent-batches"],
+            4
+        );
+        assert_eq!(settings["local-db-sync"]["base"]["batch-size"], 10);
+        assert_eq!(settings["local-db-sync"]["polygon"]["batch-size"], 5);
+    }
+
+    #[test]
+    fn orderbook_addresses_by_chain_maps_each_network() {
+        let settings = parse_settings(SETTINGS).unwrap();
+        let addresses = orderbook_addresses_by_chain(&settings);
+        assert_eq!(
+            addresses.get(&42161).map(String::as_str),
+            Some("0x8df8075e4077dabf1e95f49059e4c1eea33094ab")
+        );
+        assert_eq!(
+            addresses.get(&137).map(String::as_str),
+            Some("0x8a3C8E610d827093F7437E0C45EFa648563c0dDA")
+        );
+    }
+
+    #[test]
+    fn chain_id_for_orderbook_errors_for_unknown_orderbook() {
+        let settings = parse_settings(SETTINGS).unwrap();
+        let err = chain_id_for_orderbook(&settings, "base").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("orderbook `base` not found in settings YAML"));
+    }
+}
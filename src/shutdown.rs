@@ -0,0 +1,86 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exit status for a run interrupted by SIGINT/SIGTERM, matching the shell's 128 + SIGINT.
+pub const SHUTDOWN_EXIT_CODE: i32 = 130;
+
+#[derive(Debug)]
+pub struct ShutdownRequested;
+
+impl fmt::Display for ShutdownRequested {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sync interrupted by a shutdown request")
+    }
+}
+
+impl std::error::Error for ShutdownRequested {}
+
+pub fn is_shutdown(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<ShutdownRequested>().is_some()
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ShutdownSignal {
+    requested: Arc<AtomicBool>,
+}
+
+impl ShutdownSignal {
+    pub fn install() -> Result<Self> {
+        let signal = Self::default();
+        for signal_id in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+            signal_hook::flag::register(signal_id, signal.requested.clone())
+                .with_context(|| format!("failed to register handler for signal {signal_id}"))?;
+        }
+        Ok(signal)
+    }
+
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    pub fn sleep(&self, duration: Duration) -> bool {
+        let deadline = Instant::now() + duration;
+        loop {
+            if self.is_requested() {
+                return false;
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return true;
+            }
+            std::thread::sleep(POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleep_returns_early_when_shutdown_requested() {
+        let signal = ShutdownSignal::default();
+        signal.request();
+
+        let started = Instant::now();
+        assert!(!signal.sleep(Duration::from_secs(60)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn sleep_completes_when_not_interrupted() {
+        let signal = ShutdownSignal::default();
+        assert!(signal.sleep(Duration::from_millis(1)));
+        assert!(!signal.is_requested());
+    }
+}
@@ -3,8 +3,9 @@ mod runtime;
 #[cfg(test)]
 mod tests;
 
-pub use orchestrator::{run_sync, run_sync_with};
+pub use orchestrator::{run_plan_with, run_sync_with, run_sync_with_shutdown, run_watch_with};
+pub(crate) use runtime::normalize_yaml;
 pub use runtime::{
-    ArchiveService, CliRunner, DatabaseManager, ManifestService, SyncConfig, SyncRuntime,
-    TimeProvider,
+    ArchiveService, CliRunner, DatabaseManager, ManifestService, Publisher, SyncConfig,
+    SyncRuntime, TimeProvider, WatchConfig,
 };
@@ -1,53 +1,141 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-
-use crate::archive::{download_cli_archive, extract_cli_binary};
-use crate::cli::{run_cli_sync, RunCliSyncOptions};
-use crate::database::{finalize_database, plan_sync, prepare_database, SyncPlan};
-use crate::http::{DefaultHttpClient, HttpClient};
-use crate::manifest::{update_manifest, Manifest};
+use semver::Version;
+
+use crate::archive::{
+    cli_supports_settings_file, detect_cli_version, download_cli_archive, expected_cli_digest,
+    extract_cli_binary, CliChecksum,
+};
+use crate::cli::{run_cli_sync, CliRunSummary, RunCliSyncOptions};
+use crate::config::{ChainOverrides, FileConfig};
+use crate::constants::{
+    API_TOKEN_ENV_VARS, CLI_ARCHIVE_NAME, CLI_RETRY_INITIAL_BACKOFF, DEFAULT_RELEASE_TAG,
+    RELEASE_DOWNLOAD_URL_TEMPLATE,
+};
+use crate::database::{
+    finalize_database, inspect_database, plan_sync, prepare_database, DatabaseStats, SyncPlan,
+};
+use crate::http::{
+    log_progress, redact_url, try_sources, DefaultHttpClient, HttpClient, HttpClientConfig,
+};
+use crate::http_auth::HttpAuth;
+use crate::manifest::{update_manifest, Manifest, ManifestEntryUpdate, NetworkId};
+use crate::publish::publisher_from_env;
+use crate::release::ReleaseLocation;
+use crate::report::ArtifactSource;
+use crate::shutdown::ShutdownSignal;
+use crate::verify::verify_checksum;
 
 pub trait CliRunner: Send + Sync {
-    fn run(&self, options: &RunCliSyncOptions) -> Result<()>;
+    fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary>;
 }
 
 pub trait ArchiveService: Send + Sync {
     fn download_archive(
         &self,
         http: &dyn HttpClient,
-        url: &str,
+        urls: &[String],
         destination: &Path,
-    ) -> Result<PathBuf>;
+        checksum: &CliChecksum,
+    ) -> Result<ArtifactSource>;
+
+    fn expected_digest(
+        &self,
+        http: &dyn HttpClient,
+        url: &str,
+        checksum: &CliChecksum,
+    ) -> Option<String> {
+        expected_cli_digest(http, url, checksum)
+    }
 
     fn extract_binary(&self, archive_path: &Path, output_dir: &Path) -> Result<PathBuf>;
+
+    fn cli_version(&self, cli_binary: &Path) -> Result<Version>;
+
+    fn supports_settings_file(&self, _cli_binary: &Path) -> Result<bool> {
+        Ok(true)
+    }
 }
 
 pub trait DatabaseManager: Send + Sync {
     fn prepare_database(&self, db_stem: &str, db_dir: &Path) -> Result<(PathBuf, PathBuf)>;
     fn plan_sync(&self, db_path: &Path, dump_path: &Path) -> Result<SyncPlan>;
     fn finalize_database(&self, db_stem: &str, db_path: &Path, dump_path: &Path) -> Result<()>;
+
+    fn inspect_database(&self, _db_path: &Path) -> Result<DatabaseStats> {
+        Ok(DatabaseStats::default())
+    }
 }
 
 pub trait ManifestService: Send + Sync {
-    fn download_manifest(&self, http: &dyn HttpClient, manifest_path: &Path) -> Result<Manifest>;
+    fn download_manifest(
+        &self,
+        http: &dyn HttpClient,
+        release: &ReleaseLocation,
+        manifest_path: &Path,
+    ) -> Result<(Manifest, Option<ArtifactSource>)>;
 
-    fn download_dumps(
+    fn download_dump(
         &self,
         http: &dyn HttpClient,
+        release: &ReleaseLocation,
         manifest: &Manifest,
+        chain_id: u64,
         db_dir: &Path,
-    ) -> Result<()>;
+    ) -> Result<Option<ArtifactSource>>;
 
-    fn update_manifest(
+    fn download_dumps(
         &self,
-        manifest_path: &Path,
-        chain_id: u64,
-        download_url: &str,
-        timestamp: DateTime<Utc>,
-    ) -> Result<()>;
+        http: &dyn HttpClient,
+        release: &ReleaseLocation,
+        manifest: &Manifest,
+        chain_ids: &[u64],
+        db_dir: &Path,
+        concurrency: usize,
+    ) -> Result<Vec<ArtifactSource>> {
+        let workers = concurrency.clamp(1, chain_ids.len().max(1));
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let results = Mutex::new(Vec::new());
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while !failed.load(Ordering::SeqCst) {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(&chain_id) = chain_ids.get(index) else {
+                            break;
+                        };
+                        let result = self.download_dump(http, release, manifest, chain_id, db_dir);
+                        if result.is_err() {
+                            failed.store(true, Ordering::SeqCst);
+                        }
+                        results.lock().unwrap().push((index, result));
+                    }
+                });
+            }
+        });
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(index, _)| *index);
+        let mut sources = Vec::new();
+        for (_, result) in results {
+            sources.extend(result?);
+        }
+        Ok(sources)
+    }
+
+    fn update_manifest(&self, manifest_path: &Path, update: &ManifestEntryUpdate) -> Result<()>;
+}
+
+pub trait Publisher: Send + Sync {
+    fn publish(&self, source: &Path, name: &str) -> Result<String>;
 }
 
 pub trait TimeProvider: Send + Sync {
@@ -59,6 +147,27 @@ pub struct SyncConfig {
     pub db_dir: PathBuf,
     pub cli_dir: PathBuf,
     pub chain_ids: Vec<u64>,
+    pub orderbooks: Vec<String>,
+    pub selected_chains_only: bool,
+    pub cli_binary_url: Option<String>,
+    pub cli_binary_mirrors: Vec<String>,
+    pub cli_binary_sha256: Option<String>,
+    pub require_cli_binary_sha256: bool,
+    pub cli_version_requirement: Option<String>,
+    pub settings_yaml_url: Option<String>,
+    pub metrics_path: Option<PathBuf>,
+    pub release_url_template: String,
+    pub release_mirrors: Vec<String>,
+    pub release_tag: String,
+    pub publish_tag: Option<String>,
+    pub cli_archive_name: String,
+    pub api_token_env_vars: Vec<String>,
+    pub chains: BTreeMap<u64, ChainOverrides>,
+    pub dump_download_concurrency: Option<usize>,
+    pub cli_timeout_secs: Option<u64>,
+    pub cli_retries: u32,
+    pub cli_retry_backoff: Duration,
+    pub dry_run: bool,
 }
 
 impl Default for SyncConfig {
@@ -67,10 +176,62 @@ impl Default for SyncConfig {
             db_dir: PathBuf::from("data"),
             cli_dir: PathBuf::from("bin"),
             chain_ids: vec![],
+            orderbooks: vec![],
+            selected_chains_only: false,
+            cli_binary_url: None,
+            cli_binary_mirrors: vec![],
+            cli_binary_sha256: None,
+            require_cli_binary_sha256: false,
+            cli_version_requirement: None,
+            settings_yaml_url: None,
+            metrics_path: None,
+            release_url_template: RELEASE_DOWNLOAD_URL_TEMPLATE.to_string(),
+            release_mirrors: vec![],
+            release_tag: DEFAULT_RELEASE_TAG.to_string(),
+            publish_tag: None,
+            cli_archive_name: CLI_ARCHIVE_NAME.to_string(),
+            api_token_env_vars: API_TOKEN_ENV_VARS
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            chains: BTreeMap::new(),
+            dump_download_concurrency: None,
+            cli_timeout_secs: None,
+            cli_retries: 0,
+            cli_retry_backoff: CLI_RETRY_INITIAL_BACKOFF,
+            dry_run: false,
         }
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct WatchConfig {
+    pub interval: Duration,
+    pub max_backoff: Duration,
+}
+
+impl WatchConfig {
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            max_backoff: Self::DEFAULT_MAX_BACKOFF.max(interval),
+        }
+    }
+
+    pub fn delay_after(&self, consecutive_failures: u32) -> Duration {
+        if consecutive_failures == 0 {
+            return self.interval;
+        }
+        let factor = 1u32 << consecutive_failures.min(16);
+        self.interval
+            .max(Duration::from_secs(1))
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
 pub struct SyncRuntime {
     pub env: HashMap<String, String>,
     pub cwd: PathBuf,
@@ -80,6 +241,7 @@ pub struct SyncRuntime {
     pub database: Box<dyn DatabaseManager>,
     pub manifest: Box<dyn ManifestService>,
     pub time: Box<dyn TimeProvider>,
+    pub publisher: Option<Box<dyn Publisher>>,
 }
 
 impl Default for SyncRuntime {
@@ -102,11 +264,23 @@ impl Default for SyncRuntime {
             database,
             manifest,
             time,
+            publisher: None,
         }
     }
 }
 
 impl SyncRuntime {
+    pub fn from_env(file: Option<&FileConfig>, shutdown: &ShutdownSignal) -> Result<Self> {
+        let mut runtime = Self::default();
+        let mut http_config = HttpClientConfig::from_env(&runtime.env)?;
+        http_config.shutdown = shutdown.clone();
+        let file_auth = file.map(|file| file.http_auth.clone()).unwrap_or_default();
+        http_config.auth = HttpAuth::resolve(&file_auth, &runtime.env)?;
+        runtime.publisher = publisher_from_env(&runtime.env, &http_config)?;
+        runtime.http = Box::new(DefaultHttpClient::new(http_config)?);
+        Ok(runtime)
+    }
+
     #[allow(dead_code)]
     pub fn with_http(mut self, http: Box<dyn HttpClient>) -> Self {
         self.http = http;
@@ -118,7 +292,7 @@ impl SyncRuntime {
 struct DefaultCliRunner;
 
 impl CliRunner for DefaultCliRunner {
-    fn run(&self, options: &RunCliSyncOptions) -> Result<()> {
+    fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary> {
         run_cli_sync(options)
     }
 }
@@ -130,15 +304,24 @@ impl ArchiveService for DefaultArchiveService {
     fn download_archive(
         &self,
         http: &dyn HttpClient,
-        url: &str,
+        urls: &[String],
         destination: &Path,
-    ) -> Result<PathBuf> {
-        download_cli_archive(http, url, destination)
+        checksum: &CliChecksum,
+    ) -> Result<ArtifactSource> {
+        download_cli_archive(http, urls, destination, checksum)
     }
 
     fn extract_binary(&self, archive_path: &Path, output_dir: &Path) -> Result<PathBuf> {
         extract_cli_binary(archive_path, output_dir)
     }
+
+    fn cli_version(&self, cli_binary: &Path) -> Result<Version> {
+        detect_cli_version(cli_binary)
+    }
+
+    fn supports_settings_file(&self, cli_binary: &Path) -> Result<bool> {
+        cli_supports_settings_file(cli_binary)
+    }
 }
 
 #[derive(Default)]
@@ -156,35 +339,58 @@ impl DatabaseManager for DefaultDatabaseManager {
     fn finalize_database(&self, db_stem: &str, db_path: &Path, dump_path: &Path) -> Result<()> {
         finalize_database(db_stem, db_path, dump_path)
     }
+
+    fn inspect_database(&self, db_path: &Path) -> Result<DatabaseStats> {
+        inspect_database(db_path)
+    }
 }
 
 #[derive(Default)]
-struct DefaultManifestService;
+pub(super) struct DefaultManifestService;
 
 impl ManifestService for DefaultManifestService {
-    fn download_manifest(&self, http: &dyn HttpClient, manifest_path: &Path) -> Result<Manifest> {
+    fn download_manifest(
+        &self,
+        http: &dyn HttpClient,
+        release: &ReleaseLocation,
+        manifest_path: &Path,
+    ) -> Result<(Manifest, Option<ArtifactSource>)> {
         if let Some(parent) = manifest_path.parent() {
             std::fs::create_dir_all(parent).with_context(|| {
                 format!("failed to create manifest directory {}", parent.display())
             })?;
         }
 
-        let url =
-            crate::constants::RELEASE_DOWNLOAD_URL_TEMPLATE.replace("{file}", "manifest.yaml");
-        println!("Fetching manifest from {url}");
+        let urls = release.manifest_urls();
+        let mut parse_failed = false;
+        let fetched = try_sources("manifest.yaml", &urls, |url| {
+            println!("Fetching manifest from {}", redact_url(url));
+            let contents = http.fetch_text(url)?;
+            let manifest: Manifest = serde_yaml::from_str(&contents).map_err(|error| {
+                parse_failed = true;
+                anyhow::Error::new(error).context(format!(
+                    "failed to parse manifest downloaded from {}",
+                    redact_url(url)
+                ))
+            })?;
+            Ok((manifest, contents))
+        });
 
-        match http.fetch_text(&url) {
-            Ok(contents) => {
-                let manifest: Manifest = serde_yaml::from_str(&contents)
-                    .with_context(|| format!("failed to parse manifest downloaded from {url}"))?;
+        match fetched {
+            Ok(((manifest, contents), url)) => {
                 let normalized = normalize_yaml(&contents);
                 std::fs::write(manifest_path, &normalized).with_context(|| {
                     format!("failed to write manifest to {}", manifest_path.display())
                 })?;
-                Ok(manifest)
+                let source = ArtifactSource {
+                    artifact: "manifest.yaml".to_string(),
+                    url,
+                };
+                Ok((manifest, Some(source)))
             }
+            Err(error) if parse_failed => Err(error),
             Err(error) => {
-                println!("No manifest available at {url}; starting with empty manifest ({error})");
+                println!("No manifest available; starting with empty manifest ({error:#})");
                 let manifest = Manifest::new();
                 let serialized = normalize_yaml(
                     &serde_yaml::to_string(&manifest)
@@ -193,57 +399,69 @@ impl ManifestService for DefaultManifestService {
                 std::fs::write(manifest_path, &serialized).with_context(|| {
                     format!("failed to write manifest to {}", manifest_path.display())
                 })?;
-                Ok(manifest)
+                Ok((manifest, None))
             }
         }
     }
 
-    fn download_dumps(
+    fn download_dump(
         &self,
         http: &dyn HttpClient,
+        release: &ReleaseLocation,
         manifest: &Manifest,
+        chain_id: u64,
         db_dir: &Path,
-    ) -> Result<()> {
-        if manifest.networks.is_empty() {
-            println!("Manifest has no networks; skipping dump hydration.");
-            return Ok(());
-        }
+    ) -> Result<Option<ArtifactSource>> {
+        let Some(entry) = manifest.networks.get(&NetworkId::from(chain_id)) else {
+            println!("Manifest has no dump for chain {chain_id}; skipping dump hydration.");
+            return Ok(None);
+        };
 
         std::fs::create_dir_all(db_dir)
             .with_context(|| format!("failed to create database directory {}", db_dir.display()))?;
 
-        for network_id in manifest.networks.keys() {
-            let chain_id = u64::from(*network_id);
-            let file_name = format!("{chain_id}.sql.gz");
-            let url = crate::constants::RELEASE_DOWNLOAD_URL_TEMPLATE.replace("{file}", &file_name);
-            let destination = db_dir.join(&file_name);
-            println!("Downloading dump for chain {chain_id} from {url}");
-            let bytes = http.fetch_binary(&url).with_context(|| {
-                format!(
-                    "failed to download dump for chain {} from {}",
-                    chain_id, url
-                )
-            })?;
-            std::fs::write(&destination, &bytes).with_context(|| {
-                format!(
-                    "failed to write dump for chain {} to {}",
-                    chain_id,
-                    destination.display()
-                )
-            })?;
+        let file_name = format!("{chain_id}.sql.gz");
+        // A pinned `dump_url` names the object the publishing run actually uploaded, so try it
+        // before the release template, which may point at a tag that never received the dump.
+        let mut urls = vec![entry.dump_url.clone()];
+        for url in release.dump_urls(chain_id, &file_name)? {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
         }
-
-        Ok(())
+        urls.retain(|url| !url.is_empty());
+        let destination = db_dir.join(&file_name);
+        let (_, url) = try_sources(&file_name, &urls, |url| {
+            println!(
+                "Downloading dump for chain {chain_id} from {}",
+                redact_url(url)
+            );
+            let mut progress = log_progress(format!("Downloading dump for chain {chain_id}"));
+            http.download_to_path(url, &destination, &mut progress)?;
+            if let Some(expected) = &entry.dump_sha256 {
+                if let Err(error) = verify_checksum(&destination, expected) {
+                    let _ = std::fs::remove_file(&destination);
+                    return Err(error);
+                }
+            }
+            Ok(())
+        })
+        .with_context(|| {
+            format!(
+                "failed to download dump for chain {} to {}",
+                chain_id,
+                destination.display()
+            )
+        })?;
+
+        Ok(Some(ArtifactSource {
+            artifact: file_name,
+            url,
+        }))
     }
 
-    fn update_manifest(
-        &self,
-        manifest_path: &Path,
-        chain_id: u64,
-        download_url: &str,
-        timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        update_manifest(manifest_path, chain_id, download_url, timestamp)
+    fn update_manifest(&self, manifest_path: &Path, update: &ManifestEntryUpdate) -> Result<()> {
+        update_manifest(manifest_path, update)
     }
 }
 
@@ -361,6 +579,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn watch_config_backs_off_exponentially_up_to_cap() {
+        let watch = WatchConfig {
+            interval: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(300),
+        };
+
+        assert_eq!(watch.delay_after(0), Duration::from_secs(60));
+        assert_eq!(watch.delay_after(1), Duration::from_secs(120));
+        assert_eq!(watch.delay_after(2), Duration::from_secs(240));
+        assert_eq!(watch.delay_after(3), Duration::from_secs(300));
+        assert_eq!(watch.delay_after(40), Duration::from_secs(300));
+    }
+
     #[test]
     fn download_manifest_writes_normalized_contents() {
         let temp = tempdir().unwrap();
@@ -373,11 +605,12 @@ networks: {}
         );
         let service = DefaultManifestService;
 
-        let manifest = service
-            .download_manifest(&http, &manifest_path)
+        let (manifest, source) = service
+            .download_manifest(&http, &ReleaseLocation::default(), &manifest_path)
             .expect("manifest should load");
 
         assert_eq!(manifest.schema_version, 1);
+        assert_eq!(source.unwrap().artifact, "manifest.yaml");
         assert!(manifest_path.exists());
         let stored = std::fs::read_to_string(&manifest_path).unwrap();
         assert!(
@@ -390,6 +623,90 @@ networks: {}
         );
     }
 
+    #[test]
+    fn download_manifest_and_dumps_from_local_release_directory() {
+        let release_dir = tempdir().unwrap();
+        std::fs::write(
+            release_dir.path().join("manifest.yaml"),
+            r#"schema_version: 1
+networks:
+  7:
+    dump_url: file:///unused/7.sql.gz
+    dump_timestamp: "2024-01-01T00:00:00Z"
+"#,
+        )
+        .unwrap();
+        std::fs::write(release_dir.path().join("7.sql.gz"), b"local-dump").unwrap();
+
+        let temp = tempdir().unwrap();
+        let manifest_path = temp.path().join("manifest.yaml");
+        let db_dir = temp.path().join("db");
+        let release = ReleaseLocation::new(release_dir.path().to_str().unwrap());
+        let http = DefaultHttpClient::default();
+        let service = DefaultManifestService;
+
+        let (manifest, _) = service
+            .download_manifest(&http, &release, &manifest_path)
+            .expect("manifest should load from disk");
+        service
+            .download_dumps(&http, &release, &manifest, &[7], &db_dir, 1)
+            .expect("dumps should copy from disk");
+
+        assert_eq!(manifest.networks.len(), 1);
+        assert_eq!(
+            std::fs::read(db_dir.join("7.sql.gz")).unwrap(),
+            b"local-dump"
+        );
+    }
+
+    #[test]
+    fn download_dumps_skips_mirrors_serving_mismatched_checksums() {
+        let primary = tempdir().unwrap();
+        let mirror = tempdir().unwrap();
+        std::fs::write(primary.path().join("7.sql.gz"), b"stale-dump").unwrap();
+        std::fs::write(mirror.path().join("7.sql.gz"), b"good-dump").unwrap();
+        let expected = {
+            let staging = tempdir().unwrap();
+            let path = staging.path().join("good");
+            std::fs::write(&path, b"good-dump").unwrap();
+            crate::verify::sha256_file(&path).unwrap()
+        };
+
+        let mut manifest = Manifest::new();
+        manifest.networks.insert(
+            NetworkId::from(7u64),
+            ManifestEntry {
+                dump_url: "file:///unused/7.sql.gz".to_string(),
+                dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
+                seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+                dump_sha256: Some(expected),
+                cli_version: None,
+            },
+        );
+
+        let temp = tempdir().unwrap();
+        let release = ReleaseLocation::new(primary.path().to_str().unwrap())
+            .with_mirrors(&[mirror.path().to_str().unwrap().to_string()]);
+        let sources = DefaultManifestService
+            .download_dumps(
+                &DefaultHttpClient::default(),
+                &release,
+                &manifest,
+                &[7],
+                temp.path(),
+                1,
+            )
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(temp.path().join("7.sql.gz")).unwrap(),
+            b"good-dump"
+        );
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].artifact, "7.sql.gz");
+        assert!(sources[0].url.starts_with(mirror.path().to_str().unwrap()));
+    }
+
     #[test]
     fn download_manifest_falls_back_to_empty_manifest_on_failure() {
         let temp = tempdir().unwrap();
@@ -397,11 +714,12 @@ networks: {}
         let http = FailingTextHttpClient::new("network error");
         let service = DefaultManifestService;
 
-        let manifest = service
-            .download_manifest(&http, &manifest_path)
+        let (manifest, source) = service
+            .download_manifest(&http, &ReleaseLocation::default(), &manifest_path)
             .expect("fallback manifest should be created");
 
         assert_eq!(manifest.networks.len(), 0);
+        assert!(source.is_none());
         assert!(manifest_path.exists());
         let stored = std::fs::read_to_string(&manifest_path).unwrap();
         assert!(
@@ -426,6 +744,8 @@ networks: {}
                     dump_url: "https://example.com/123.sql.gz".to_string(),
                     dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
                     seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+                    dump_sha256: None,
+                    cli_version: None,
                 },
             )]
             .into_iter()
@@ -435,17 +755,59 @@ networks: {}
         let service = DefaultManifestService;
 
         service
-            .download_dumps(&http, &manifest, db_dir)
+            .download_dumps(
+                &http,
+                &ReleaseLocation::default(),
+                &manifest,
+                &[123],
+                db_dir,
+                1,
+            )
             .expect("dumps should download");
 
         let dump_path = db_dir.join("123.sql.gz");
         assert!(dump_path.exists());
         let bytes = std::fs::read(&dump_path).unwrap();
         assert_eq!(bytes, b"dump-bytes");
+        assert_eq!(http.requests(), vec!["https://example.com/123.sql.gz"]);
+    }
+
+    #[test]
+    fn download_dumps_falls_back_to_the_release_template_after_the_manifest_dump_url() {
+        let release_dir = tempdir().unwrap();
+        std::fs::write(release_dir.path().join("7.sql.gz"), b"template-dump").unwrap();
+        let mut manifest = Manifest::new();
+        manifest.networks.insert(
+            NetworkId::from(7u64),
+            ManifestEntry {
+                dump_url: "file:///unused/v3/7.sql.gz".to_string(),
+                dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
+                seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+                dump_sha256: None,
+                cli_version: None,
+            },
+        );
+
+        let temp = tempdir().unwrap();
+        let release = ReleaseLocation::new(release_dir.path().to_str().unwrap());
+        let sources = DefaultManifestService
+            .download_dumps(
+                &DefaultHttpClient::default(),
+                &release,
+                &manifest,
+                &[7],
+                temp.path(),
+                1,
+            )
+            .unwrap();
+
         assert_eq!(
-            http.requests(),
-            vec![crate::constants::RELEASE_DOWNLOAD_URL_TEMPLATE.replace("{file}", "123.sql.gz")]
+            std::fs::read(temp.path().join("7.sql.gz")).unwrap(),
+            b"template-dump"
         );
+        assert!(sources[0]
+            .url
+            .starts_with(release_dir.path().to_str().unwrap()));
     }
 
     #[test]
@@ -457,10 +819,58 @@ networks: {}
         let service = DefaultManifestService;
 
         service
-            .download_dumps(&http, &manifest, db_dir)
-            .expect("empty manifest should skip downloads");
+            .download_dumps(
+                &http,
+                &ReleaseLocation::default(),
+                &manifest,
+                &[1],
+                db_dir,
+                1,
+            )
+            .expect("chains missing from the manifest should skip downloads");
 
         assert!(std::fs::read_dir(db_dir).unwrap().next().is_none());
         assert!(http.requests().is_empty());
     }
+
+    #[test]
+    fn download_dumps_fetches_chains_concurrently_in_order() {
+        let temp = tempdir().unwrap();
+        let db_dir = temp.path();
+        let mut manifest = Manifest::new();
+        for chain_id in [1u64, 2, 3] {
+            manifest.networks.insert(
+                NetworkId::from(chain_id),
+                ManifestEntry {
+                    dump_url: format!("https://example.com/{chain_id}.sql.gz"),
+                    dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
+                    seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+                    dump_sha256: None,
+                    cli_version: None,
+                },
+            );
+        }
+        let http = BinaryHttpClient::new(b"dump-bytes");
+
+        let sources = DefaultManifestService
+            .download_dumps(
+                &http,
+                &ReleaseLocation::default(),
+                &manifest,
+                &[3, 1, 2],
+                db_dir,
+                4,
+            )
+            .expect("dumps should download");
+
+        let artifacts: Vec<_> = sources
+            .iter()
+            .map(|source| source.artifact.as_str())
+            .collect();
+        assert_eq!(artifacts, vec!["3.sql.gz", "1.sql.gz", "2.sql.gz"]);
+        assert_eq!(http.requests().len(), 3);
+        for chain_id in [1, 2, 3] {
+            assert!(db_dir.join(format!("{chain_id}.sql.gz")).exists());
+        }
+    }
 }
@@ -1,25 +1,35 @@
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use chrono::TimeZone;
+use semver::Version;
 use tempfile::tempdir;
 
-use super::orchestrator::run_sync_with;
+use super::orchestrator::{run_sync_with, run_sync_with_shutdown, run_watch_with};
 use super::runtime::{
-    normalize_yaml, ArchiveService, CliRunner, DatabaseManager, ManifestService, SyncConfig,
-    SyncRuntime, TimeProvider,
+    normalize_yaml, ArchiveService, CliRunner, DatabaseManager, DefaultManifestService,
+    ManifestService, Publisher, SyncConfig, SyncRuntime, TimeProvider, WatchConfig,
 };
-use crate::cli::RunCliSyncOptions;
+use crate::archive::CliChecksum;
+use crate::cli::{CliRunSummary, CliTimeoutError, RunCliSyncOptions};
+use crate::config::resolve_sync_config;
 use crate::constants::{
     API_TOKEN_ENV_VARS, CLI_ARCHIVE_NAME, CLI_BINARY_URL_ENV_VAR, RELEASE_DOWNLOAD_URL_TEMPLATE,
     SETTINGS_YAML_ENV_VAR, SYNC_CHAIN_IDS_ENV_VAR,
 };
 use crate::database::SyncPlan;
-use crate::http::HttpClient;
-use crate::manifest::{Manifest, ManifestEntry, NetworkId};
+use crate::http::{DefaultHttpClient, HttpClient};
+use crate::manifest::{load_manifest, Manifest, ManifestEntry, ManifestEntryUpdate, NetworkId};
+use crate::publish::LocalDirectoryPublisher;
+use crate::release::ReleaseLocation;
+use crate::report::{ArtifactSource, CliProgressEvent};
+use crate::settings::LocalDbSyncTuning;
+use crate::shutdown::{is_shutdown, ShutdownSignal};
 
 #[derive(Clone, Default)]
 struct MockCliRunner {
@@ -30,6 +40,9 @@ struct MockCliRunner {
 struct MockCliRunnerState {
     calls: Mutex<Vec<RunCliSyncOptions>>,
     fail_next: Mutex<Option<String>>,
+    fail_always: Mutex<Option<String>>,
+    events: Mutex<Vec<CliProgressEvent>>,
+    time_out_chains: Mutex<Vec<u64>>,
 }
 
 impl MockCliRunner {
@@ -40,15 +53,47 @@ impl MockCliRunner {
     fn fail_next_with(&self, message: &str) {
         *self.inner.fail_next.lock().unwrap() = Some(message.to_string());
     }
+
+    fn fail_always_with(&self, message: &str) {
+        *self.inner.fail_always.lock().unwrap() = Some(message.to_string());
+    }
+
+    fn time_out_chain(&self, chain_id: u64) {
+        self.inner.time_out_chains.lock().unwrap().push(chain_id);
+    }
+
+    fn report_events(&self, events: Vec<CliProgressEvent>) {
+        *self.inner.events.lock().unwrap() = events;
+    }
 }
 
 impl CliRunner for MockCliRunner {
-    fn run(&self, options: &RunCliSyncOptions) -> Result<()> {
+    fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary> {
         self.inner.calls.lock().unwrap().push(options.clone());
         if let Some(message) = self.inner.fail_next.lock().unwrap().take() {
             anyhow::bail!(message);
         }
-        Ok(())
+        if let Some(message) = self.inner.fail_always.lock().unwrap().clone() {
+            anyhow::bail!(message);
+        }
+        if self
+            .inner
+            .time_out_chains
+            .lock()
+            .unwrap()
+            .contains(&options.chain_id)
+        {
+            return Err(CliTimeoutError {
+                chain_id: options.chain_id,
+                timeout: options.timeout.unwrap_or_default(),
+                log_path: PathBuf::from("chain.cli.log"),
+            }
+            .into());
+        }
+        Ok(CliRunSummary {
+            log_path: PathBuf::from(&options.db_path).with_extension("cli.log"),
+            events: self.inner.events.lock().unwrap().clone(),
+        })
     }
 }
 
@@ -60,7 +105,10 @@ struct MockArchiveService {
 #[derive(Default)]
 struct MockArchiveState {
     download_calls: Mutex<Vec<(String, PathBuf)>>,
+    checksums: Mutex<Vec<CliChecksum>>,
     extract_calls: Mutex<Vec<(PathBuf, PathBuf)>>,
+    cli_version: Mutex<Option<Version>>,
+    legacy_cli: AtomicBool,
 }
 
 impl MockArchiveService {
@@ -68,6 +116,20 @@ impl MockArchiveService {
         self.inner.download_calls.lock().unwrap().clone()
     }
 
+    fn with_cli_version(self, version: &str) -> Self {
+        *self.inner.cli_version.lock().unwrap() = Some(Version::parse(version).unwrap());
+        self
+    }
+
+    fn with_legacy_cli(self) -> Self {
+        self.inner.legacy_cli.store(true, Ordering::SeqCst);
+        self
+    }
+
+    fn checksums(&self) -> Vec<CliChecksum> {
+        self.inner.checksums.lock().unwrap().clone()
+    }
+
     fn extract_calls(&self) -> Vec<(PathBuf, PathBuf)> {
         self.inner.extract_calls.lock().unwrap().clone()
     }
@@ -77,19 +139,33 @@ impl ArchiveService for MockArchiveService {
     fn download_archive(
         &self,
         _http: &dyn HttpClient,
-        url: &str,
+        urls: &[String],
         destination: &Path,
-    ) -> Result<PathBuf> {
+        checksum: &CliChecksum,
+    ) -> Result<ArtifactSource> {
+        self.inner.checksums.lock().unwrap().push(checksum.clone());
         self.inner
             .download_calls
             .lock()
             .unwrap()
-            .push((url.to_string(), destination.to_path_buf()));
+            .push((urls[0].clone(), destination.to_path_buf()));
         if let Some(parent) = destination.parent() {
             std::fs::create_dir_all(parent)?;
         }
         std::fs::write(destination, b"archive-bytes")?;
-        Ok(destination.to_path_buf())
+        Ok(ArtifactSource {
+            artifact: "cli".to_string(),
+            url: urls[0].clone(),
+        })
+    }
+
+    fn expected_digest(
+        &self,
+        _http: &dyn HttpClient,
+        _url: &str,
+        checksum: &CliChecksum,
+    ) -> Option<String> {
+        checksum.expected.clone()
     }
 
     fn extract_binary(&self, archive_path: &Path, output_dir: &Path) -> Result<PathBuf> {
@@ -103,9 +179,23 @@ impl ArchiveService for MockArchiveService {
         std::fs::write(&binary_path, b"#!/bin/sh\necho mock\n")?;
         Ok(binary_path)
     }
+
+    fn cli_version(&self, _cli_binary: &Path) -> Result<Version> {
+        Ok(self
+            .inner
+            .cli_version
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| Version::new(0, 0, 1)))
+    }
+
+    fn supports_settings_file(&self, _cli_binary: &Path) -> Result<bool> {
+        Ok(!self.inner.legacy_cli.load(Ordering::SeqCst))
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct MockDatabaseManager {
     inner: Arc<MockDatabaseState>,
 }
@@ -115,6 +205,7 @@ struct MockDatabaseState {
     plan_calls: Mutex<Vec<(PathBuf, PathBuf)>>,
     finalize_calls: Mutex<Vec<(String, PathBuf, PathBuf)>>,
     plan_template: Mutex<SyncPlan>,
+    queued_plans: Mutex<VecDeque<Option<u64>>>,
 }
 
 impl Default for MockDatabaseState {
@@ -129,6 +220,7 @@ impl Default for MockDatabaseState {
                 last_synced_block: None,
                 next_start_block: None,
             }),
+            queued_plans: Default::default(),
         }
     }
 }
@@ -143,6 +235,14 @@ impl MockDatabaseManager {
         }
     }
 
+    fn queue_last_synced_blocks(&self, blocks: &[Option<u64>]) {
+        self.inner
+            .queued_plans
+            .lock()
+            .unwrap()
+            .extend(blocks.iter().copied());
+    }
+
     fn prepare_calls(&self) -> Vec<(String, PathBuf)> {
         self.inner.prepare_calls.lock().unwrap().clone()
     }
@@ -179,6 +279,10 @@ impl DatabaseManager for MockDatabaseManager {
         let mut template = self.inner.plan_template.lock().unwrap().clone();
         template.db_path = db_path.to_path_buf();
         template.dump_path = dump_path.to_path_buf();
+        if let Some(last_synced_block) = self.inner.queued_plans.lock().unwrap().pop_front() {
+            template.last_synced_block = last_synced_block;
+            template.next_start_block = last_synced_block.map(|block| block + 1);
+        }
         Ok(template)
     }
 
@@ -206,8 +310,9 @@ type ManifestUpdate = (PathBuf, u64, String, chrono::DateTime<chrono::Utc>);
 struct MockManifestState {
     manifest: Manifest,
     download_calls: Mutex<Vec<PathBuf>>,
-    download_dumps_calls: Mutex<Vec<PathBuf>>,
+    dump_calls: Mutex<Vec<(u64, PathBuf)>>,
     updates: Mutex<Vec<ManifestUpdate>>,
+    cli_versions: Mutex<Vec<Option<String>>>,
 }
 
 impl MockManifestService {
@@ -216,8 +321,9 @@ impl MockManifestService {
             inner: Arc::new(MockManifestState {
                 manifest,
                 download_calls: Default::default(),
-                download_dumps_calls: Default::default(),
+                dump_calls: Default::default(),
                 updates: Default::default(),
+                cli_versions: Default::default(),
             }),
         }
     }
@@ -226,56 +332,89 @@ impl MockManifestService {
         self.inner.download_calls.lock().unwrap().clone()
     }
 
-    fn download_dumps_calls(&self) -> Vec<PathBuf> {
-        self.inner.download_dumps_calls.lock().unwrap().clone()
+    fn dump_calls(&self) -> Vec<(u64, PathBuf)> {
+        self.inner.dump_calls.lock().unwrap().clone()
     }
 
     fn updates(&self) -> Vec<ManifestUpdate> {
         self.inner.updates.lock().unwrap().clone()
     }
+
+    fn cli_versions(&self) -> Vec<Option<String>> {
+        self.inner.cli_versions.lock().unwrap().clone()
+    }
 }
 
 impl ManifestService for MockManifestService {
-    fn download_manifest(&self, _http: &dyn HttpClient, manifest_path: &Path) -> Result<Manifest> {
+    fn download_manifest(
+        &self,
+        _http: &dyn HttpClient,
+        _release: &ReleaseLocation,
+        manifest_path: &Path,
+    ) -> Result<(Manifest, Option<ArtifactSource>)> {
         self.inner
             .download_calls
             .lock()
             .unwrap()
             .push(manifest_path.to_path_buf());
-        Ok(self.inner.manifest.clone())
+        Ok((self.inner.manifest.clone(), None))
     }
 
-    fn download_dumps(
+    fn download_dump(
         &self,
         _http: &dyn HttpClient,
+        _release: &ReleaseLocation,
         _manifest: &Manifest,
+        chain_id: u64,
         db_dir: &Path,
-    ) -> Result<()> {
+    ) -> Result<Option<ArtifactSource>> {
         self.inner
-            .download_dumps_calls
+            .dump_calls
             .lock()
             .unwrap()
-            .push(db_dir.to_path_buf());
-        Ok(())
+            .push((chain_id, db_dir.to_path_buf()));
+        Ok(None)
     }
 
-    fn update_manifest(
-        &self,
-        manifest_path: &Path,
-        chain_id: u64,
-        download_url: &str,
-        timestamp: chrono::DateTime<chrono::Utc>,
-    ) -> Result<()> {
+    fn update_manifest(&self, manifest_path: &Path, update: &ManifestEntryUpdate) -> Result<()> {
         self.inner.updates.lock().unwrap().push((
             manifest_path.to_path_buf(),
-            chain_id,
-            download_url.to_string(),
-            timestamp,
+            update.chain_id,
+            update.dump_url.clone(),
+            update.dump_timestamp,
         ));
+        self.inner
+            .cli_versions
+            .lock()
+            .unwrap()
+            .push(update.cli_version.clone());
         Ok(())
     }
 }
 
+type PublishedArtifact = (String, Option<Vec<u8>>);
+
+#[derive(Clone, Default)]
+struct MockPublisher {
+    published: Arc<Mutex<Vec<PublishedArtifact>>>,
+}
+
+impl MockPublisher {
+    fn published(&self) -> Vec<PublishedArtifact> {
+        self.published.lock().unwrap().clone()
+    }
+}
+
+impl Publisher for MockPublisher {
+    fn publish(&self, source: &Path, name: &str) -> Result<String> {
+        self.published
+            .lock()
+            .unwrap()
+            .push((name.to_string(), std::fs::read(source).ok()));
+        Ok(format!("mock://{name}"))
+    }
+}
+
 #[derive(Clone)]
 struct MockTimeProvider {
     times: Arc<Mutex<VecDeque<chrono::DateTime<chrono::Utc>>>>,
@@ -372,6 +511,8 @@ fn manifest_with_chain(chain_id: u64) -> Manifest {
             dump_url: format!("https://example.com/{chain_id}.sql.gz"),
             dump_timestamp: "2024-01-01T00:00:00Z".to_string(),
             seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+            dump_sha256: None,
+            cli_version: None,
         },
     );
     manifest
@@ -415,6 +556,7 @@ fn run_sync_with_uses_injected_services() {
         database: Box::new(database.clone()),
         manifest: Box::new(manifest_service.clone()),
         time: Box::new(time_provider.clone()),
+        publisher: None,
     };
 
     run_sync_with(runtime, SyncConfig::default()).unwrap();
@@ -445,8 +587,8 @@ fn run_sync_with_uses_injected_services() {
     let manifest_downloads = manifest_service.download_calls();
     assert_eq!(manifest_downloads, vec![cwd.join("data/manifest.yaml")]);
 
-    let dumps_calls = manifest_service.download_dumps_calls();
-    assert_eq!(dumps_calls, vec![cwd.join("data")]);
+    let dump_calls = manifest_service.dump_calls();
+    assert_eq!(dump_calls, vec![(chain_id, cwd.join("data"))]);
 
     let updates = manifest_service.updates();
     assert_eq!(updates.len(), 1);
@@ -485,15 +627,20 @@ fn run_sync_with_fails_when_archive_download_fails() {
         fn download_archive(
             &self,
             _http: &dyn HttpClient,
-            _url: &str,
+            _urls: &[String],
             _destination: &Path,
-        ) -> Result<PathBuf> {
+            _checksum: &CliChecksum,
+        ) -> Result<ArtifactSource> {
             anyhow::bail!("archive download failed");
         }
 
         fn extract_binary(&self, _archive_path: &Path, _output_dir: &Path) -> Result<PathBuf> {
             unreachable!("extract should not be called");
         }
+
+        fn cli_version(&self, _cli_binary: &Path) -> Result<Version> {
+            unreachable!("cli_version should not be called");
+        }
     }
 
     let temp = tempdir().unwrap();
@@ -520,6 +667,7 @@ fn run_sync_with_fails_when_archive_download_fails() {
         database: Box::new(database),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -527,6 +675,206 @@ fn run_sync_with_fails_when_archive_download_fails() {
     assert!(cli_runner.calls().is_empty());
 }
 
+#[test]
+fn run_sync_with_passes_cli_checksum_to_archive_service() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default();
+    let runtime = |archive: &MockArchiveService, syncs: usize| SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(archive.clone()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(MockManifestService::new(Manifest::new())),
+        time: Box::new(make_time_provider(2 + 2 * syncs)),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        cli_binary_sha256: Some(format!("{}  cli.tar.gz", "AB".repeat(32))),
+        require_cli_binary_sha256: true,
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime(&archive, 0), config).unwrap();
+    assert_eq!(
+        archive.checksums(),
+        vec![CliChecksum {
+            expected: Some("ab".repeat(32)),
+            required: true,
+        }]
+    );
+
+    let config = SyncConfig {
+        cli_binary_sha256: Some("deadbeef".to_string()),
+        ..SyncConfig::default()
+    };
+    let err = run_sync_with(runtime(&archive, 0), config).unwrap_err();
+    assert!(format!("{err:#}").contains("invalid CLI binary checksum"));
+    assert_eq!(archive.checksums().len(), 1);
+}
+
+fn cli_version_runtime(
+    cwd: &Path,
+    archive: &MockArchiveService,
+    cli_runner: &MockCliRunner,
+    manifest_service: &MockManifestService,
+) -> SyncRuntime {
+    SyncRuntime {
+        env: base_env(),
+        cwd: cwd.to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(archive.clone()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(make_time_provider(4)),
+        publisher: None,
+    }
+}
+
+#[test]
+fn run_sync_with_records_cli_version_in_manifest_and_metrics() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default().with_cli_version("0.0.4");
+    let cli_runner = MockCliRunner::default();
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+    let runtime = cli_version_runtime(temp.path(), &archive, &cli_runner, &manifest_service);
+    let config = SyncConfig {
+        metrics_path: Some(PathBuf::from("sync.prom")),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    assert_eq!(
+        manifest_service.cli_versions(),
+        vec![Some("0.0.4".to_string())]
+    );
+    let metrics = std::fs::read_to_string(temp.path().join("sync.prom")).unwrap();
+    assert!(metrics.contains("rain_local_db_cli_info{version=\"0.0.4\"} 1"));
+}
+
+#[test]
+fn run_sync_with_reports_cli_progress_events_in_metrics() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default();
+    let cli_runner = MockCliRunner::default();
+    cli_runner.report_events(vec![
+        CliProgressEvent::BatchesFetched { count: 4 },
+        CliProgressEvent::BlocksProcessed { block: 1_200 },
+        CliProgressEvent::BatchesFetched { count: 6 },
+    ]);
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+    let runtime = cli_version_runtime(temp.path(), &archive, &cli_runner, &manifest_service);
+    let config = SyncConfig {
+        metrics_path: Some(PathBuf::from("sync.prom")),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let metrics = std::fs::read_to_string(temp.path().join("sync.prom")).unwrap();
+    assert!(metrics.contains("rain_local_db_cli_batches_fetched{chain_id=\"1\"} 10\n"));
+    assert!(metrics.contains("rain_local_db_cli_last_processed_block{chain_id=\"1\"} 1200\n"));
+}
+
+#[test]
+fn run_sync_with_rejects_clis_without_settings_file_support() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default().with_legacy_cli();
+    let cli_runner = MockCliRunner::default();
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+
+    let runtime = cli_version_runtime(temp.path(), &archive, &cli_runner, &manifest_service);
+    let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
+    assert!(err.to_string().contains("does not accept --settings-file"));
+    assert!(cli_runner.calls().is_empty());
+    assert!(manifest_service.updates().is_empty());
+}
+
+#[test]
+fn run_sync_with_rejects_unsupported_cli_versions() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default().with_cli_version("1.2.0");
+    let cli_runner = MockCliRunner::default();
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+
+    let runtime = cli_version_runtime(temp.path(), &archive, &cli_runner, &manifest_service);
+    let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("CLI version 1.2.0 is not supported for manifest schema version 1"));
+    assert!(cli_runner.calls().is_empty());
+    assert!(manifest_service.updates().is_empty());
+
+    let runtime = cli_version_runtime(temp.path(), &archive, &cli_runner, &manifest_service);
+    let config = SyncConfig {
+        cli_version_requirement: Some(">=1.2, <2".to_string()),
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime, config).unwrap();
+    assert_eq!(cli_runner.calls().len(), 1);
+}
+
+#[test]
+fn run_sync_with_reuses_cached_cli_binary_across_runs() {
+    let temp = tempdir().unwrap();
+    let archive = MockArchiveService::default();
+    let cli_runner = MockCliRunner::default();
+    let runtime = || SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(archive.clone()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(1))),
+        time: Box::new(make_time_provider(4)),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_binary_sha256: Some("ab".repeat(32)),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime(), config.clone()).unwrap();
+    std::fs::remove_file(temp.path().join("bin/rain-orderbook-cli")).unwrap();
+    run_sync_with(runtime(), config.clone()).unwrap();
+
+    assert_eq!(archive.download_calls().len(), 1);
+    assert_eq!(archive.extract_calls().len(), 1);
+    let calls = cli_runner.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].cli_binary, calls[1].cli_binary);
+    assert_eq!(
+        std::fs::read(temp.path().join("bin/rain-orderbook-cli")).unwrap(),
+        b"#!/bin/sh\necho mock\n"
+    );
+
+    let config = SyncConfig {
+        cli_binary_sha256: Some("cd".repeat(32)),
+        ..config
+    };
+    run_sync_with(runtime(), config).unwrap();
+    assert_eq!(archive.download_calls().len(), 2);
+}
+
 #[test]
 fn run_sync_with_fails_when_archive_extract_fails() {
     struct ExtractFailArchive;
@@ -535,19 +883,27 @@ fn run_sync_with_fails_when_archive_extract_fails() {
         fn download_archive(
             &self,
             _http: &dyn HttpClient,
-            _url: &str,
+            urls: &[String],
             destination: &Path,
-        ) -> Result<PathBuf> {
+            _checksum: &CliChecksum,
+        ) -> Result<ArtifactSource> {
             if let Some(parent) = destination.parent() {
                 std::fs::create_dir_all(parent)?;
             }
             std::fs::write(destination, b"bytes")?;
-            Ok(destination.to_path_buf())
+            Ok(ArtifactSource {
+                artifact: "cli".to_string(),
+                url: urls[0].clone(),
+            })
         }
 
         fn extract_binary(&self, _archive_path: &Path, _output_dir: &Path) -> Result<PathBuf> {
             anyhow::bail!("archive extract failed");
         }
+
+        fn cli_version(&self, _cli_binary: &Path) -> Result<Version> {
+            unreachable!("cli_version should not be called");
+        }
     }
 
     let temp = tempdir().unwrap();
@@ -574,6 +930,7 @@ fn run_sync_with_fails_when_archive_extract_fails() {
         database: Box::new(database),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -613,6 +970,7 @@ fn run_sync_with_propagates_cli_error() {
         database: Box::new(database.clone()),
         manifest: Box::new(manifest_service.clone()),
         time: Box::new(time_provider.clone()),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -660,10 +1018,13 @@ fn run_sync_with_processes_manifest_and_config_chain_ids() {
         database: Box::new(database.clone()),
         manifest: Box::new(manifest_service.clone()),
         time: Box::new(time_provider.clone()),
+        publisher: None,
     };
 
-    let mut config = SyncConfig::default();
-    config.chain_ids.push(config_chain);
+    let config = SyncConfig {
+        chain_ids: vec![manifest_chain, config_chain],
+        ..SyncConfig::default()
+    };
 
     run_sync_with(runtime, config).unwrap();
 
@@ -703,6 +1064,164 @@ fn run_sync_with_processes_manifest_and_config_chain_ids() {
     assert_eq!(time_provider.remaining(), 0);
 }
 
+fn manifest_with_chains(chain_ids: &[u64]) -> Manifest {
+    let mut manifest = Manifest::new();
+    for &chain_id in chain_ids {
+        manifest
+            .networks
+            .extend(manifest_with_chain(chain_id).networks);
+    }
+    manifest
+}
+
+fn dump_sync_runtime(
+    cwd: &Path,
+    manifest_service: &MockManifestService,
+    syncs: usize,
+) -> SyncRuntime {
+    SyncRuntime {
+        env: base_env(),
+        cwd: cwd.to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(make_time_provider(2 + 2 * syncs)),
+        publisher: None,
+    }
+}
+
+#[test]
+fn run_sync_with_syncs_only_selected_chains_when_selection_is_exclusive() {
+    let temp = tempdir().unwrap();
+    let manifest_service = MockManifestService::new(manifest_with_chains(&[1, 3]));
+    let runtime = dump_sync_runtime(temp.path(), &manifest_service, 1);
+    let config = SyncConfig {
+        chain_ids: vec![3],
+        selected_chains_only: true,
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    assert_eq!(
+        manifest_service.dump_calls(),
+        vec![(3, temp.path().join("data"))]
+    );
+    let updates = manifest_service.updates();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].1, 3);
+}
+
+#[test]
+fn run_sync_with_downloads_each_dump_lazily_in_chain_order() {
+    let temp = tempdir().unwrap();
+    let manifest_service = MockManifestService::new(manifest_with_chains(&[1, 3]));
+    let runtime = dump_sync_runtime(temp.path(), &manifest_service, 3);
+    let config = SyncConfig {
+        chain_ids: vec![1, 2, 3],
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let db_dir = temp.path().join("data");
+    assert_eq!(
+        manifest_service.dump_calls(),
+        vec![(1, db_dir.clone()), (2, db_dir.clone()), (3, db_dir)]
+    );
+}
+
+#[test]
+fn run_sync_with_prefetches_selected_dumps_concurrently() {
+    let temp = tempdir().unwrap();
+    let manifest_service = MockManifestService::new(manifest_with_chains(&[1, 2, 3, 4]));
+    let runtime = dump_sync_runtime(temp.path(), &manifest_service, 5);
+    let config = SyncConfig {
+        chain_ids: vec![1, 2, 3, 4, 5],
+        dump_download_concurrency: Some(2),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let mut chains: Vec<u64> = manifest_service
+        .dump_calls()
+        .into_iter()
+        .map(|(chain_id, _)| chain_id)
+        .collect();
+    chains.sort_unstable();
+    assert_eq!(chains, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn run_sync_with_never_fetches_dumps_of_chains_filtered_out_by_env() {
+    #[derive(Clone, Default)]
+    struct ReleaseHttpClient {
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpClient for ReleaseHttpClient {
+        fn fetch_text(&self, url: &str) -> Result<String> {
+            self.requests.lock().unwrap().push(url.to_string());
+            if url.ends_with("manifest.yaml") {
+                return Ok(serde_yaml::to_string(&manifest_with_chains(&[1, 2, 3]))?);
+            }
+            Ok("settings: true".to_string())
+        }
+
+        fn fetch_binary(&self, url: &str) -> Result<Vec<u8>> {
+            self.requests.lock().unwrap().push(url.to_string());
+            Ok(b"dump-bytes".to_vec())
+        }
+    }
+
+    let temp = tempdir().unwrap();
+    let http = ReleaseHttpClient::default();
+    let cli_runner = MockCliRunner::default();
+    let mut env = base_env();
+    env.insert(SYNC_CHAIN_IDS_ENV_VAR.to_string(), "2".to_string());
+    let config = SyncConfig {
+        release_url_template: "https://release.example/{file}".to_string(),
+        dump_download_concurrency: Some(4),
+        ..resolve_sync_config(None, &env).unwrap()
+    };
+    let runtime = SyncRuntime {
+        env,
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(http.clone()),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::default()),
+        manifest: Box::new(DefaultManifestService),
+        time: Box::new(make_time_provider(4)),
+        publisher: None,
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let chains: Vec<u64> = cli_runner
+        .calls()
+        .iter()
+        .map(|call| call.chain_id)
+        .collect();
+    assert_eq!(chains, vec![2]);
+    let requests = http.requests.lock().unwrap().clone();
+    assert!(requests.iter().any(|url| url.ends_with("/2.sql.gz")));
+    for filtered in ["1.sql.gz", "3.sql.gz"] {
+        assert!(
+            !requests.iter().any(|url| url.ends_with(filtered)),
+            "{filtered} was fetched: {requests:?}"
+        );
+    }
+}
+
 #[test]
 fn run_sync_with_processes_env_chain_ids() {
     let temp = tempdir().unwrap();
@@ -727,6 +1246,7 @@ fn run_sync_with_processes_env_chain_ids() {
         SYNC_CHAIN_IDS_ENV_VAR.to_string(),
         "101, 202,303".to_string(),
     );
+    let config = resolve_sync_config(None, &env).unwrap();
 
     let runtime = SyncRuntime {
         env,
@@ -737,9 +1257,10 @@ fn run_sync_with_processes_env_chain_ids() {
         database: Box::new(database.clone()),
         manifest: Box::new(manifest_service.clone()),
         time: Box::new(time_provider.clone()),
+        publisher: None,
     };
 
-    run_sync_with(runtime, SyncConfig::default()).unwrap();
+    run_sync_with(runtime, config).unwrap();
 
     let calls = cli_runner.calls();
     assert_eq!(calls.len(), 3);
@@ -785,26 +1306,27 @@ fn run_sync_with_fails_when_manifest_download_fails() {
         fn download_manifest(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest_path: &Path,
-        ) -> Result<Manifest> {
+        ) -> Result<(Manifest, Option<ArtifactSource>)> {
             anyhow::bail!("manifest download failed");
         }
 
-        fn download_dumps(
+        fn download_dump(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest: &Manifest,
+            _chain_id: u64,
             _db_dir: &Path,
-        ) -> Result<()> {
-            unreachable!("download_dumps not expected");
+        ) -> Result<Option<ArtifactSource>> {
+            unreachable!("download_dump not expected");
         }
 
         fn update_manifest(
             &self,
             _manifest_path: &Path,
-            _chain_id: u64,
-            _download_url: &str,
-            _timestamp: chrono::DateTime<chrono::Utc>,
+            _update: &ManifestEntryUpdate,
         ) -> Result<()> {
             unreachable!("update_manifest not expected");
         }
@@ -834,6 +1356,7 @@ fn run_sync_with_fails_when_manifest_download_fails() {
         database: Box::new(database),
         manifest: Box::new(DownloadFailManifest),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -851,26 +1374,27 @@ fn run_sync_with_fails_when_manifest_dump_hydration_fails() {
         fn download_manifest(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest_path: &Path,
-        ) -> Result<Manifest> {
-            Ok(self.manifest.clone())
+        ) -> Result<(Manifest, Option<ArtifactSource>)> {
+            Ok((self.manifest.clone(), None))
         }
 
-        fn download_dumps(
+        fn download_dump(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest: &Manifest,
+            _chain_id: u64,
             _db_dir: &Path,
-        ) -> Result<()> {
+        ) -> Result<Option<ArtifactSource>> {
             anyhow::bail!("dump hydration failed");
         }
 
         fn update_manifest(
             &self,
             _manifest_path: &Path,
-            _chain_id: u64,
-            _download_url: &str,
-            _timestamp: chrono::DateTime<chrono::Utc>,
+            _update: &ManifestEntryUpdate,
         ) -> Result<()> {
             unreachable!("update_manifest not expected");
         }
@@ -902,6 +1426,7 @@ fn run_sync_with_fails_when_manifest_dump_hydration_fails() {
             manifest: manifest_with_chain(100),
         }),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -929,31 +1454,32 @@ fn run_sync_with_fails_when_manifest_update_fails() {
         fn download_manifest(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest_path: &Path,
-        ) -> Result<Manifest> {
-            Ok(self.manifest.clone())
+        ) -> Result<(Manifest, Option<ArtifactSource>)> {
+            Ok((self.manifest.clone(), None))
         }
 
-        fn download_dumps(
+        fn download_dump(
             &self,
             _http: &dyn HttpClient,
+            _release: &ReleaseLocation,
             _manifest: &Manifest,
+            _chain_id: u64,
             _db_dir: &Path,
-        ) -> Result<()> {
-            Ok(())
+        ) -> Result<Option<ArtifactSource>> {
+            Ok(None)
         }
 
         fn update_manifest(
             &self,
             manifest_path: &Path,
-            chain_id: u64,
-            download_url: &str,
-            _timestamp: chrono::DateTime<chrono::Utc>,
+            update: &ManifestEntryUpdate,
         ) -> Result<()> {
             self.updates.lock().unwrap().push((
                 manifest_path.to_path_buf(),
-                chain_id,
-                download_url.to_string(),
+                update.chain_id,
+                update.dump_url.clone(),
             ));
             anyhow::bail!("manifest update failed");
         }
@@ -989,6 +1515,7 @@ fn run_sync_with_fails_when_manifest_update_fails() {
         database: Box::new(database.clone()),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider.clone()),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1048,6 +1575,7 @@ fn run_sync_with_fails_when_database_prepare_fails() {
         database: Box::new(PrepareFailDatabase),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1103,6 +1631,7 @@ fn run_sync_with_fails_when_database_plan_fails() {
         database: Box::new(PlanFailDatabase),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1165,6 +1694,7 @@ fn run_sync_with_fails_when_database_finalize_fails() {
         database: Box::new(FinalizeFailDatabase),
         manifest: Box::new(manifest_service),
         time: Box::new(time_provider),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1198,6 +1728,7 @@ fn run_sync_with_errors_when_cli_binary_url_missing() {
         })),
         manifest: Box::new(MockManifestService::new(Manifest::new())),
         time: Box::new(make_time_provider(1)),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1230,6 +1761,7 @@ fn run_sync_with_errors_when_settings_yaml_missing() {
         })),
         manifest: Box::new(MockManifestService::new(Manifest::new())),
         time: Box::new(make_time_provider(1)),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1262,6 +1794,7 @@ fn run_sync_with_errors_when_api_token_missing() {
         })),
         manifest: Box::new(MockManifestService::new(Manifest::new())),
         time: Box::new(make_time_provider(2)),
+        publisher: None,
     };
 
     let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
@@ -1270,3 +1803,860 @@ fn run_sync_with_errors_when_api_token_missing() {
         "unexpected error: {err}"
     );
 }
+
+#[test]
+fn run_sync_with_writes_metrics_textfile() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 42161u64;
+    let cli_runner = MockCliRunner::default();
+    let database = MockDatabaseManager::new(SyncPlan {
+        db_path: PathBuf::new(),
+        dump_path: PathBuf::new(),
+        last_synced_block: Some(1),
+        next_start_block: Some(2),
+    });
+    let time_provider = make_time_provider(4);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(chain_id))),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        metrics_path: Some(PathBuf::from("metrics/sync.prom")),
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime, config).unwrap();
+
+    let contents = std::fs::read_to_string(cwd.join("metrics/sync.prom")).unwrap();
+    assert!(contents.contains("rain_local_db_last_run_success 1\n"));
+    assert!(contents.contains("rain_local_db_run_duration_seconds 3\n"));
+    assert!(contents.contains(&format!(
+        "rain_local_db_dump_bytes{{chain_id=\"{chain_id}\"}} 16\n"
+    )));
+    assert!(contents.contains(&format!(
+        "rain_local_db_last_sync_completed_ms{{chain_id=\"{chain_id}\"}} 1704067202000\n"
+    )));
+    assert!(contents.contains(&format!(
+        "rain_local_db_sync_failures_total{{chain_id=\"{chain_id}\"}} 0\n"
+    )));
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_retries_cli_from_the_latest_watermark() {
+    let temp = tempdir().unwrap();
+    let cli_runner = MockCliRunner::default();
+    cli_runner.fail_next_with("rpc connection reset");
+    let database = MockDatabaseManager::default();
+    database.queue_last_synced_blocks(&[Some(100), Some(250)]);
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+    let time_provider = make_time_provider(4);
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database.clone()),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_retries: 2,
+        cli_retry_backoff: Duration::ZERO,
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let calls = cli_runner.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].start_block, Some(101));
+    assert_eq!(calls[1].start_block, Some(251));
+    assert_eq!(calls[0].db_path, calls[1].db_path);
+    assert_eq!(database.plan_calls().len(), 2);
+    assert_eq!(database.finalize_calls().len(), 1);
+    assert_eq!(manifest_service.updates().len(), 1);
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_gives_up_after_exhausting_cli_retries() {
+    let temp = tempdir().unwrap();
+    let cli_runner = MockCliRunner::default();
+    cli_runner.fail_always_with("rpc connection reset");
+    let database = MockDatabaseManager::default();
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database.clone()),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(make_time_provider(2)),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_retries: 1,
+        cli_retry_backoff: Duration::ZERO,
+        ..SyncConfig::default()
+    };
+
+    let err = run_sync_with(runtime, config).unwrap_err();
+    assert!(err.to_string().contains("rpc connection reset"));
+    assert_eq!(cli_runner.calls().len(), 2);
+    assert_eq!(database.plan_calls().len(), 2);
+    assert!(database.finalize_calls().is_empty());
+    assert!(manifest_service.updates().is_empty());
+    assert!(!temp.path().join("data/1.db").exists());
+}
+
+#[test]
+fn run_sync_with_does_not_retry_cli_timeouts() {
+    let temp = tempdir().unwrap();
+    let cli_runner = MockCliRunner::default();
+    cli_runner.time_out_chain(1);
+    let database = MockDatabaseManager::default();
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database.clone()),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(1))),
+        time: Box::new(make_time_provider(2)),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_retries: 2,
+        ..SyncConfig::default()
+    };
+
+    let err = run_sync_with(runtime, config).unwrap_err();
+    assert!(err.to_string().contains("CLI sync timed out for chains 1"));
+    assert_eq!(cli_runner.calls().len(), 1);
+    assert_eq!(database.plan_calls().len(), 1);
+}
+
+#[test]
+fn run_sync_with_stops_retrying_once_shutdown_is_requested() {
+    struct ShutdownDuringRun {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl CliRunner for ShutdownDuringRun {
+        fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            options.shutdown.request();
+            anyhow::bail!("CLI sync for chain {} was stopped", options.chain_id)
+        }
+    }
+
+    let temp = tempdir().unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(ShutdownDuringRun {
+            calls: calls.clone(),
+        }),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::default()),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(1))),
+        time: Box::new(make_time_provider(2)),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_retries: 3,
+        ..SyncConfig::default()
+    };
+
+    let shutdown = ShutdownSignal::default();
+    let err = run_sync_with_shutdown(runtime, config, shutdown.clone()).unwrap_err();
+    assert!(is_shutdown(&err));
+    assert!(format!("{err:#}").contains("was stopped"));
+    assert!(shutdown.is_requested());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn run_sync_with_fails_without_publishing_when_shutdown_interrupts_the_pass() {
+    struct ShutdownAfterSync {
+        inner: MockCliRunner,
+    }
+
+    impl CliRunner for ShutdownAfterSync {
+        fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary> {
+            let summary = self.inner.run(options)?;
+            options.shutdown.request();
+            Ok(summary)
+        }
+    }
+
+    let temp = tempdir().unwrap();
+    let cli_runner = MockCliRunner::default();
+    let manifest_service = MockManifestService::new(manifest_with_chains(&[10, 20]));
+    let publisher = MockPublisher::default();
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(ShutdownAfterSync {
+            inner: cli_runner.clone(),
+        }),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::default()),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(make_time_provider(4)),
+        publisher: Some(Box::new(publisher.clone())),
+    };
+    let config = SyncConfig {
+        metrics_path: Some(PathBuf::from("sync.prom")),
+        ..SyncConfig::default()
+    };
+
+    let err = run_sync_with_shutdown(runtime, config, ShutdownSignal::default()).unwrap_err();
+
+    assert!(is_shutdown(&err), "{err:#}");
+    assert_eq!(cli_runner.calls().len(), 1);
+    assert!(publisher.published().is_empty());
+    assert!(manifest_service.updates().is_empty());
+    let metrics = std::fs::read_to_string(temp.path().join("sync.prom")).unwrap();
+    assert!(
+        metrics.contains("rain_local_db_last_run_success 0\n"),
+        "{metrics}"
+    );
+}
+
+#[test]
+fn run_sync_with_backs_off_between_cli_retries_until_shutdown() {
+    let temp = tempdir().unwrap();
+    let cli_runner = MockCliRunner::default();
+    cli_runner.fail_always_with("rpc connection reset");
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::default()),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(1))),
+        time: Box::new(make_time_provider(2)),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_retries: 3,
+        cli_retry_backoff: Duration::from_secs(60),
+        ..SyncConfig::default()
+    };
+
+    let shutdown = ShutdownSignal::default();
+    let requester = {
+        let shutdown = shutdown.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            shutdown.request();
+        })
+    };
+    let started = std::time::Instant::now();
+    let err = run_sync_with_shutdown(runtime, config, shutdown).unwrap_err();
+    requester.join().unwrap();
+
+    assert!(is_shutdown(&err));
+    assert!(format!("{err:#}").contains("rpc connection reset"));
+    assert_eq!(cli_runner.calls().len(), 1);
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(300));
+    assert!(elapsed < Duration::from_secs(10));
+}
+
+#[test]
+fn run_sync_with_continues_past_cli_timeouts_and_fails_the_run() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let cli_runner = MockCliRunner::default();
+    cli_runner.time_out_chain(1);
+    let manifest_service = MockManifestService::new(manifest_with_chains(&[1, 2]));
+    let time_provider = make_time_provider(5);
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+    let config = SyncConfig {
+        cli_timeout_secs: Some(30),
+        metrics_path: Some(PathBuf::from("sync.prom")),
+        ..SyncConfig::default()
+    };
+
+    let err = run_sync_with(runtime, config).unwrap_err();
+    assert!(err.to_string().contains("CLI sync timed out for chains 1"));
+
+    let calls = cli_runner.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].timeout, Some(Duration::from_secs(30)));
+    let updates = manifest_service.updates();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].1, 2);
+
+    let contents = std::fs::read_to_string(cwd.join("sync.prom")).unwrap();
+    assert!(contents.contains("rain_local_db_cli_timed_out{chain_id=\"1\"} 1\n"));
+    assert!(contents.contains("rain_local_db_cli_timed_out{chain_id=\"2\"} 0\n"));
+    assert!(contents.contains("rain_local_db_sync_failures_total{chain_id=\"1\"} 1\n"));
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_records_failures_in_metrics() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 10u64;
+    let cli_runner = MockCliRunner::default();
+    cli_runner.fail_next_with("cli failed");
+    let time_provider = make_time_provider(3);
+    let mut env = base_env();
+    env.insert(
+        crate::constants::METRICS_TEXTFILE_ENV_VAR.to_string(),
+        "sync.prom".to_string(),
+    );
+
+    let runtime = SyncRuntime {
+        env,
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(chain_id))),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let err = run_sync_with(runtime, SyncConfig::default()).unwrap_err();
+    assert!(err.to_string().contains("cli failed"));
+
+    let contents = std::fs::read_to_string(cwd.join("sync.prom")).unwrap();
+    assert!(contents.contains("rain_local_db_last_run_success 0\n"));
+    assert!(contents.contains(&format!(
+        "rain_local_db_sync_failures_total{{chain_id=\"{chain_id}\"}} 1\n"
+    )));
+    assert!(contents.contains(&format!(
+        "rain_local_db_last_sync_completed_ms{{chain_id=\"{chain_id}\"}} 1704067200000\n"
+    )));
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_watch_with_reuses_cli_and_dumps_until_shutdown() {
+    struct ShutdownAfterCalls {
+        calls: Arc<Mutex<Vec<RunCliSyncOptions>>>,
+        shutdown: ShutdownSignal,
+        limit: usize,
+        fail_first: bool,
+    }
+
+    impl CliRunner for ShutdownAfterCalls {
+        fn run(&self, options: &RunCliSyncOptions) -> Result<CliRunSummary> {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push(options.clone());
+            if calls.len() >= self.limit {
+                self.shutdown.request();
+            }
+            if self.fail_first && calls.len() == 1 {
+                anyhow::bail!("transient cli failure");
+            }
+            Ok(CliRunSummary::default())
+        }
+    }
+
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 42161u64;
+    let shutdown = ShutdownSignal::default();
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let archive = MockArchiveService::default();
+    let manifest_service = MockManifestService::new(manifest_with_chain(chain_id));
+    let http_client = StubHttpClient::new("settings: true");
+    let time_provider = make_time_provider(4);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(http_client.clone()),
+        cli_runner: Box::new(ShutdownAfterCalls {
+            calls: calls.clone(),
+            shutdown: shutdown.clone(),
+            limit: 2,
+            fail_first: true,
+        }),
+        archive: Box::new(archive.clone()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        chain_ids: vec![chain_id],
+        ..SyncConfig::default()
+    };
+    let watch = WatchConfig {
+        interval: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+    };
+    run_watch_with(runtime, config, watch, shutdown.clone()).unwrap();
+
+    assert!(shutdown.is_requested());
+    assert_eq!(calls.lock().unwrap().len(), 2);
+    assert_eq!(archive.download_calls().len(), 1);
+    assert_eq!(archive.extract_calls().len(), 1);
+    assert_eq!(manifest_service.download_calls().len(), 1);
+    assert_eq!(manifest_service.dump_calls().len(), 1);
+    assert!(manifest_service.updates().is_empty());
+    assert_eq!(http_client.requests().len(), 2);
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_watch_with_exits_immediately_when_shutdown_already_requested() {
+    let temp = tempdir().unwrap();
+    let shutdown = ShutdownSignal::default();
+    shutdown.request();
+    let cli_runner = MockCliRunner::default();
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: temp.path().to_path_buf(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(1))),
+        time: Box::new(make_time_provider(0)),
+        publisher: None,
+    };
+
+    run_watch_with(
+        runtime,
+        SyncConfig::default(),
+        WatchConfig::new(Duration::from_secs(60)),
+        shutdown,
+    )
+    .unwrap();
+    assert!(cli_runner.calls().is_empty());
+}
+
+#[test]
+fn run_sync_with_dry_run_plans_without_running_cli_or_updating_manifest() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let cli_runner = MockCliRunner::default();
+    let database = MockDatabaseManager::new(SyncPlan {
+        db_path: PathBuf::new(),
+        dump_path: PathBuf::new(),
+        last_synced_block: Some(10),
+        next_start_block: Some(11),
+    });
+    let manifest_service = MockManifestService::new(manifest_with_chain(1));
+    let time_provider = make_time_provider(2);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database.clone()),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        chain_ids: vec![1, 2],
+        metrics_path: Some(PathBuf::from("sync.prom")),
+        dry_run: true,
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime, config).unwrap();
+
+    assert_eq!(manifest_service.download_calls().len(), 1);
+    let dump_chains: Vec<u64> = manifest_service
+        .dump_calls()
+        .into_iter()
+        .map(|(chain_id, _)| chain_id)
+        .collect();
+    assert_eq!(dump_chains, vec![1, 2]);
+    assert_eq!(database.prepare_calls().len(), 2);
+    assert_eq!(database.plan_calls().len(), 2);
+    assert!(cli_runner.calls().is_empty());
+    assert!(database.finalize_calls().is_empty());
+    assert!(manifest_service.updates().is_empty());
+    assert!(!cwd.join("data/1.db").exists());
+    assert!(!cwd.join("sync.prom").exists());
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_applies_per_chain_overrides() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 42161u64;
+    let cli_runner = MockCliRunner::default();
+    let database = MockDatabaseManager::new(SyncPlan {
+        db_path: PathBuf::new(),
+        dump_path: PathBuf::new(),
+        last_synced_block: Some(10),
+        next_start_block: Some(11),
+    });
+    let time_provider = make_time_provider(4);
+    let mut env = base_env();
+    env.insert("ARBITRUM_TOKEN".to_string(), "arbitrum-token".to_string());
+
+    let runtime = SyncRuntime {
+        env,
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new(
+            "networks:\n  arbitrum:\n    chain-id: 42161\nlocal-db-sync:\n  arbitrum:\n    max-concurrent-batches: 15\n",
+        )),
+        cli_runner: Box::new(cli_runner.clone()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(chain_id))),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let overrides = crate::config::ChainOverrides {
+        start_block: Some(100),
+        end_block: Some(500),
+        api_token_env_var: Some("ARBITRUM_TOKEN".to_string()),
+        concurrency: Some(3),
+        cli_timeout_secs: Some(900),
+        local_db_sync: LocalDbSyncTuning {
+            rate_limit_delay_ms: Some(750),
+            ..LocalDbSyncTuning::default()
+        },
+    };
+    let config = SyncConfig {
+        chains: [(chain_id, overrides)].into_iter().collect(),
+        cli_timeout_secs: Some(60),
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime, config).unwrap();
+
+    let calls = cli_runner.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].start_block, Some(100));
+    assert_eq!(calls[0].end_block, Some(500));
+    assert_eq!(calls[0].api_token.as_deref(), Some("arbitrum-token"));
+    assert_eq!(calls[0].timeout, Some(Duration::from_secs(900)));
+    assert_eq!(
+        calls[0].tuning,
+        LocalDbSyncTuning {
+            max_concurrent_batches: Some(3),
+            rate_limit_delay_ms: Some(750),
+            ..LocalDbSyncTuning::default()
+        }
+    );
+    let mut settings: serde_yaml::Value = serde_yaml::from_str(&calls[0].settings_yaml).unwrap();
+    let network =
+        crate::settings::apply_local_db_sync_tuning(&mut settings, chain_id, &calls[0].tuning)
+            .unwrap();
+    assert_eq!(network, "arbitrum");
+    assert_eq!(
+        settings["local-db-sync"]["arbitrum"]["max-concurrent-batches"],
+        3
+    );
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_writes_dump_urls_from_release_template() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 8453u64;
+    let manifest_service = MockManifestService::new(manifest_with_chain(chain_id));
+    let time_provider = make_time_provider(4);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new(
+            "networks:\n  base:\n    chain-id: 8453\norderbooks:\n  base:\n    address: 0x52CEB8eBEf648744fFDDE89F7Bc9C3aC35944775\n    network: base\n",
+        )),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        release_url_template: "https://cdn.example/{tag}/{chain}/{orderbook}/{file}".to_string(),
+        release_tag: "staging".to_string(),
+        ..SyncConfig::default()
+    };
+    run_sync_with(runtime, config).unwrap();
+
+    let updates = manifest_service.updates();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(
+        updates[0].2,
+        "https://cdn.example/staging/8453/0x52CEB8eBEf648744fFDDE89F7Bc9C3aC35944775/8453.sql.gz"
+    );
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_pins_dump_urls_to_release_tag() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let chain_id = 137u64;
+    let manifest_service = MockManifestService::new(manifest_with_chain(chain_id));
+    let time_provider = make_time_provider(4);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(time_provider.clone()),
+        publisher: None,
+    };
+
+    let config = SyncConfig {
+        publish_tag: Some("sync-1234".to_string()),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let updates = manifest_service.updates();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(
+        updates[0].2,
+        "https://github.com/findolor/local_db_remote/releases/download/sync-1234/137.sql.gz"
+    );
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_publishes_dumps_after_finalize_and_manifest_last() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let mut manifest = manifest_with_chain(10);
+    manifest.networks.extend(manifest_with_chain(20).networks);
+    let database = MockDatabaseManager::new(SyncPlan {
+        db_path: PathBuf::new(),
+        dump_path: PathBuf::new(),
+        last_synced_block: None,
+        next_start_block: None,
+    });
+    let publisher = MockPublisher::default();
+    let time_provider = make_time_provider(6);
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(database.clone()),
+        manifest: Box::new(MockManifestService::new(manifest)),
+        time: Box::new(time_provider.clone()),
+        publisher: Some(Box::new(publisher.clone())),
+    };
+
+    run_sync_with(runtime, SyncConfig::default()).unwrap();
+
+    let published = publisher.published();
+    let names: Vec<&str> = published.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["10.sql.gz", "20.sql.gz", "manifest.yaml"]);
+    assert_eq!(published[0].1.as_deref(), Some(&b"compressed-bytes"[..]));
+    assert_eq!(database.finalize_calls().len(), 2);
+    assert_eq!(time_provider.remaining(), 0);
+}
+
+#[test]
+fn run_sync_with_publishes_dumps_under_their_manifest_dump_url() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let manifest_service = MockManifestService::new(manifest_with_chain(10));
+    let publisher = MockPublisher::default();
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(MockCliRunner::default()),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(manifest_service.clone()),
+        time: Box::new(make_time_provider(4)),
+        publisher: Some(Box::new(publisher.clone())),
+    };
+    let config = SyncConfig {
+        release_url_template: "https://cdn.example/dumps/{tag}/{file}".to_string(),
+        publish_tag: Some("v7".to_string()),
+        ..SyncConfig::default()
+    };
+
+    run_sync_with(runtime, config).unwrap();
+
+    let published = publisher.published();
+    let names: Vec<&str> = published.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["v7/10.sql.gz", "latest/manifest.yaml"]);
+    let updates = manifest_service.updates();
+    assert_eq!(updates[0].2, "https://cdn.example/dumps/v7/10.sql.gz");
+}
+
+#[test]
+fn run_sync_with_hydrates_pinned_dumps_published_by_the_previous_run() {
+    let temp = tempdir().unwrap();
+    let release_dir = temp.path().join("release");
+    let settings_path = temp.path().join("settings.yaml");
+    std::fs::write(&settings_path, "settings: true").unwrap();
+    let config = SyncConfig {
+        release_url_template: format!("{}/{{tag}}/{{file}}", release_dir.display()),
+        settings_yaml_url: Some(settings_path.display().to_string()),
+        ..SyncConfig::default()
+    };
+    let run = |cwd: &Path, publish_tag: &str| {
+        let cli_runner = MockCliRunner::default();
+        let runtime = SyncRuntime {
+            env: base_env(),
+            cwd: cwd.to_path_buf(),
+            http: Box::new(DefaultHttpClient::default()),
+            cli_runner: Box::new(cli_runner.clone()),
+            archive: Box::new(MockArchiveService::default()),
+            database: Box::new(MockDatabaseManager::default()),
+            manifest: Box::new(DefaultManifestService),
+            time: Box::new(make_time_provider(4)),
+            publisher: Some(Box::new(LocalDirectoryPublisher::new(&release_dir))),
+        };
+        let config = SyncConfig {
+            chain_ids: vec![10],
+            publish_tag: Some(publish_tag.to_string()),
+            ..config.clone()
+        };
+        run_sync_with(runtime, config).map(|_| cli_runner)
+    };
+
+    let first = temp.path().join("first");
+    run(&first, "v7").unwrap();
+    assert!(release_dir.join("v7/10.sql.gz").exists());
+    assert!(release_dir.join("latest/manifest.yaml").exists());
+    assert!(!release_dir.join("latest/10.sql.gz").exists());
+
+    let second = temp.path().join("second");
+    run(&second, "v8").unwrap();
+    assert_eq!(
+        std::fs::read(second.join("data/10.sql.gz")).unwrap(),
+        b"compressed-bytes"
+    );
+    let manifest = load_manifest(&release_dir.join("latest/manifest.yaml")).unwrap();
+    assert_eq!(
+        manifest.networks[&NetworkId::from(10u64)].dump_url,
+        format!("{}/v8/10.sql.gz", release_dir.display())
+    );
+}
+
+#[test]
+fn run_sync_with_skips_manifest_publish_when_chain_fails() {
+    let temp = tempdir().unwrap();
+    let cwd = temp.path().to_path_buf();
+
+    let cli_runner = MockCliRunner::default();
+    cli_runner.fail_next_with("cli failed");
+    let publisher = MockPublisher::default();
+
+    let runtime = SyncRuntime {
+        env: base_env(),
+        cwd: cwd.clone(),
+        http: Box::new(StubHttpClient::new("settings: true")),
+        cli_runner: Box::new(cli_runner),
+        archive: Box::new(MockArchiveService::default()),
+        database: Box::new(MockDatabaseManager::new(SyncPlan {
+            db_path: PathBuf::new(),
+            dump_path: PathBuf::new(),
+            last_synced_block: None,
+            next_start_block: None,
+        })),
+        manifest: Box::new(MockManifestService::new(manifest_with_chain(10))),
+        time: Box::new(make_time_provider(2)),
+        publisher: Some(Box::new(publisher.clone())),
+    };
+
+    run_sync_with(runtime, SyncConfig::default()).unwrap_err();
+    assert!(publisher.published().is_empty());
+}
@@ -0,0 +1,210 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use sha2::{Digest, Sha256};
+
+use crate::manifest::{load_manifest, Manifest};
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("failed to hash {}", path.display()))?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
+    let actual = sha256_file(path)?;
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        anyhow::bail!(
+            "checksum mismatch for {}: expected sha256 {}, got {actual}",
+            path.display(),
+            expected.trim()
+        );
+    }
+    Ok(())
+}
+
+pub fn verify_data_dir(db_dir: &Path) -> Result<Vec<String>> {
+    let manifest_path = db_dir.join("manifest.yaml");
+    if !manifest_path.exists() {
+        anyhow::bail!("no manifest found at {}", manifest_path.display());
+    }
+    let manifest = load_manifest(&manifest_path)?;
+
+    let mut issues = Vec::new();
+    if manifest.schema_version != Manifest::CURRENT_SCHEMA_VERSION {
+        issues.push(format!(
+            "manifest schema version {} does not match expected {}",
+            manifest.schema_version,
+            Manifest::CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    for (network_id, entry) in &manifest.networks {
+        let chain_id = u64::from(*network_id);
+        let file_name = format!("{chain_id}.sql.gz");
+
+        if !entry.dump_url.ends_with(&file_name) {
+            issues.push(format!(
+                "chain {chain_id}: dump_url {} does not point at {file_name}",
+                entry.dump_url
+            ));
+        }
+        if DateTime::parse_from_rfc3339(&entry.dump_timestamp).is_err() {
+            issues.push(format!(
+                "chain {chain_id}: dump_timestamp `{}` is not RFC 3339",
+                entry.dump_timestamp
+            ));
+        }
+
+        let dump_path = db_dir.join(&file_name);
+        match fs::metadata(&dump_path) {
+            Ok(metadata) if metadata.len() == 0 => {
+                issues.push(format!(
+                    "chain {chain_id}: dump {} is empty",
+                    dump_path.display()
+                ));
+            }
+            Ok(_) => {
+                if !gzip_is_valid(&dump_path)? {
+                    issues.push(format!(
+                        "chain {chain_id}: dump {} is not a valid gzip archive",
+                        dump_path.display()
+                    ));
+                }
+                if let Some(expected) = &entry.dump_sha256 {
+                    if let Err(error) = verify_checksum(&dump_path, expected) {
+                        issues.push(format!("chain {chain_id}: {error}"));
+                    }
+                }
+            }
+            Err(_) => {
+                issues.push(format!(
+                    "chain {chain_id}: dump {} is missing",
+                    dump_path.display()
+                ));
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+fn gzip_is_valid(path: &Path) -> Result<bool> {
+    let status = Command::new("gzip")
+        .arg("-t")
+        .arg(path)
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("failed to spawn gzip to test {}", path.display()))?;
+    Ok(status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{update_manifest, ManifestEntry, ManifestEntryUpdate, NetworkId};
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn write_gzip(path: &Path, contents: &[u8]) {
+        let staging = tempdir().unwrap();
+        let source = staging.path().join("dump.sql");
+        fs::write(&source, contents).unwrap();
+        let output = Command::new("gzip")
+            .arg("-c")
+            .arg(&source)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        fs::write(path, output.stdout).unwrap();
+    }
+
+    #[test]
+    fn verify_data_dir_accepts_consistent_manifest_and_dumps() {
+        let dir = tempdir().unwrap();
+        let manifest_path = dir.path().join("manifest.yaml");
+        update_manifest(
+            &manifest_path,
+            &ManifestEntryUpdate {
+                chain_id: 10,
+                dump_url: "https://example.com/download/10.sql.gz".to_string(),
+                dump_timestamp: Utc::now(),
+                dump_sha256: None,
+                cli_version: None,
+            },
+        )
+        .unwrap();
+        write_gzip(&dir.path().join("10.sql.gz"), b"CREATE TABLE t;\n");
+
+        let issues = verify_data_dir(dir.path()).unwrap();
+        assert!(issues.is_empty(), "unexpected issues: {issues:?}");
+    }
+
+    #[test]
+    fn verify_data_dir_reports_checksum_mismatch() {
+        let dir = tempdir().unwrap();
+        update_manifest(
+            &dir.path().join("manifest.yaml"),
+            &ManifestEntryUpdate {
+                chain_id: 10,
+                dump_url: "https://example.com/download/10.sql.gz".to_string(),
+                dump_timestamp: Utc::now(),
+                dump_sha256: Some("00".repeat(32)),
+                cli_version: None,
+            },
+        )
+        .unwrap();
+        write_gzip(&dir.path().join("10.sql.gz"), b"CREATE TABLE t;\n");
+
+        let issues = verify_data_dir(dir.path()).unwrap();
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0].contains("checksum mismatch"));
+    }
+
+    #[test]
+    fn verify_data_dir_reports_missing_and_corrupt_dumps() {
+        let dir = tempdir().unwrap();
+        let manifest_path = dir.path().join("manifest.yaml");
+        let mut manifest = Manifest::new();
+        for chain_id in [1u64, 2u64] {
+            manifest.networks.insert(
+                NetworkId::from(chain_id),
+                ManifestEntry {
+                    dump_url: format!("https://example.com/{chain_id}.sql.gz"),
+                    dump_timestamp: "not-a-timestamp".to_string(),
+                    seed_generation: ManifestEntry::DEFAULT_SEED_GENERATION,
+                    dump_sha256: None,
+                    cli_version: None,
+                },
+            );
+        }
+        fs::write(&manifest_path, serde_yaml::to_string(&manifest).unwrap()).unwrap();
+        fs::write(dir.path().join("2.sql.gz"), b"not gzip").unwrap();
+
+        let issues = verify_data_dir(dir.path()).unwrap();
+        assert!(issues
+            .iter()
+            .any(|issue| issue.contains("1.sql.gz is missing")));
+        assert!(issues
+            .iter()
+            .any(|issue| issue.contains("2.sql.gz is not a valid gzip archive")));
+        assert!(issues.iter().any(|issue| issue.contains("not RFC 3339")));
+    }
+
+    #[test]
+    fn verify_data_dir_errors_without_manifest() {
+        let dir = tempdir().unwrap();
+        let err = verify_data_dir(dir.path()).unwrap_err();
+        assert!(err.to_string().contains("no manifest found"));
+    }
+}